@@ -0,0 +1,220 @@
+//! Compares two benchmark result files written by `FrameBench`
+//!
+//! Runs are matched up by scene, mode, enemy count, area scale and seed and
+//! the average frame time of every timed scope is compared. Exits with a
+//! non-zero code when any scope got slower by more than the threshold
+//!
+//! ```bash
+//! bench_compare <baseline> <current> [threshold_percent]
+//! ```
+#![deny(clippy::all)]
+#![warn(clippy::pedantic, clippy::nursery, unsafe_code)]
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::process;
+
+const DEFAULT_THRESHOLD: f64 = 5.0;
+
+/// The parts of a `BenchSummary` that are compared
+#[derive(Deserialize)]
+struct Summary {
+    /// `null` when the run didn't record any frames
+    avg: Option<f64>,
+}
+
+/// A single line of a benchmark file
+#[derive(Deserialize)]
+struct Run {
+    scene: String,
+    mode: String,
+    enemy_count: usize,
+    area_scale: f32,
+    seed: u64,
+    main: Summary,
+    systems: BTreeMap<String, Summary>,
+}
+
+impl Run {
+    fn key(&self) -> RunKey {
+        RunKey {
+            scene: self.scene.clone(),
+            mode: self.mode.clone(),
+            enemy_count: self.enemy_count,
+            area_scale: self.area_scale.to_bits(),
+            seed: self.seed,
+        }
+    }
+}
+
+/// Everything that has to be the same for two runs to be comparable
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct RunKey {
+    scene: String,
+    /// Rendered and headless runs are never compared with each other
+    mode: String,
+    enemy_count: usize,
+    /// Bits of the scale so that keys can be ordered, scales are read
+    /// back exactly as they were written so equal scales have equal bits
+    area_scale: u32,
+    seed: u64,
+}
+
+/// The columns of the table that identify a run
+impl fmt::Display for RunKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:<16} {:<8} {:>8} {:>6} {:>20}",
+            self.scene,
+            self.mode,
+            self.enemy_count,
+            f32::from_bits(self.area_scale),
+            self.seed
+        )
+    }
+}
+
+fn read_runs(path: &str) -> Result<BTreeMap<RunKey, Run>, String> {
+    let file = File::open(path).map_err(|e| format!("Couldn't open {}: {}", path, e))?;
+    let mut runs = BTreeMap::new();
+
+    for (number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("Couldn't read {}: {}", path, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let run: Run = serde_json::from_str(&line)
+            .map_err(|e| format!("{}:{} is not a benchmark result: {}", path, number + 1, e))?;
+
+        // Results are appended so reruns end up in the same file, only the last one is used
+        let key = run.key();
+        if runs.insert(key.clone(), run).is_some() {
+            eprintln!(
+                "Warning: {}:{} repeats an earlier run, only the last one is compared: {}",
+                path,
+                number + 1,
+                key
+            );
+        }
+    }
+
+    Ok(runs)
+}
+
+/// Prints a row of the table and returns whether it is a regression
+///
+/// Rows without a usable average on both sides are flagged but never
+/// count as a regression
+fn compare(
+    key: &RunKey,
+    scope: &str,
+    baseline: Option<f64>,
+    current: Option<f64>,
+    threshold: f64,
+) -> bool {
+    let (baseline, current) = match (baseline, current) {
+        (Some(baseline), Some(current)) if baseline > 0.0 => (baseline, current),
+        _ => {
+            println!("{} {:<20} no data", key, scope);
+            return false;
+        }
+    };
+
+    let delta = (current - baseline) / baseline * 100.0;
+    let regressed = delta > threshold;
+
+    println!(
+        "{} {:<20} {:>12.4} {:>12.4} {:>+8.2}% {}",
+        key,
+        scope,
+        baseline * 1000.0,
+        current * 1000.0,
+        delta,
+        if regressed { "REGRESSION" } else { "" }
+    );
+
+    regressed
+}
+
+fn run(args: &[String]) -> Result<bool, String> {
+    if args.len() < 3 || args.len() > 4 {
+        return Err(format!(
+            "Usage: {} <baseline> <current> [threshold_percent]",
+            args[0]
+        ));
+    }
+
+    let threshold = if let Some(threshold) = args.get(3) {
+        threshold
+            .parse()
+            .map_err(|_| format!("Invalid threshold: '{}'", threshold))?
+    } else {
+        DEFAULT_THRESHOLD
+    };
+
+    let baseline = read_runs(&args[1])?;
+    let current = read_runs(&args[2])?;
+
+    println!(
+        "{:<16} {:<8} {:>8} {:>6} {:>20} {:<20} {:>12} {:>12} {:>9}",
+        "scene", "mode", "enemies", "scale", "seed", "scope", "base (ms)", "curr (ms)", "delta"
+    );
+
+    let mut regressed = false;
+    for (key, base_run) in &baseline {
+        let curr_run = if let Some(run) = current.get(key) {
+            run
+        } else {
+            println!("{} missing from {}", key, args[2]);
+            continue;
+        };
+
+        regressed |= compare(
+            key,
+            "frame",
+            base_run.main.avg,
+            curr_run.main.avg,
+            threshold,
+        );
+
+        for (scope, base) in &base_run.systems {
+            let curr = curr_run.systems.get(scope).and_then(|curr| curr.avg);
+            regressed |= compare(key, scope, base.avg, curr, threshold);
+        }
+        // Scopes that are new have nothing to be compared to
+        for (scope, curr) in &curr_run.systems {
+            if !base_run.systems.contains_key(scope) {
+                regressed |= compare(key, scope, None, curr.avg, threshold);
+            }
+        }
+    }
+
+    for key in current.keys() {
+        if !baseline.contains_key(key) {
+            println!("{} missing from {}", key, args[1]);
+        }
+    }
+
+    Ok(regressed)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    match run(&args) {
+        Ok(false) => {}
+        Ok(true) => {
+            eprintln!("Performance regressed by more than the threshold");
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use amethyst::ecs::prelude::{Component, DenseVecStorage};
+use serde::{Deserialize, Serialize};
+
+/// The different ways an enemy can move
+///
+/// Behaviours that follow the player use the closest player
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BehaviorMode {
+    /// Moves straight towards the player
+    Chase,
+    /// Moves straight away from the player
+    Flee,
+    /// Drifts around changing direction randomly
+    Wander,
+    /// Circles around the player at a distance
+    Orbit { radius: f32 },
+}
+
+/// Enemies with this component steer themselves by changing their velocity
+///
+/// Enemies without it keep going in a straight line
+#[derive(Clone, Debug)]
+pub struct EnemyBehavior {
+    pub mode: BehaviorMode,
+    pub max_speed: f32,
+    /// How quickly the velocity can change (units/s²)
+    pub max_acceleration: f32,
+    /// Direction that wandering enemies want to go in radians
+    pub wander_angle: f32,
+}
+
+impl EnemyBehavior {
+    pub const fn new(mode: BehaviorMode, max_speed: f32, max_acceleration: f32) -> Self {
+        Self {
+            mode,
+            max_speed,
+            max_acceleration,
+            wander_angle: 0.0,
+        }
+    }
+}
+
+impl Component for EnemyBehavior {
+    type Storage = DenseVecStorage<Self>;
+}
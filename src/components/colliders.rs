@@ -65,10 +65,14 @@ impl Collisions {
     }
 
     pub fn reset(&mut self) {
-        // SAFETY: Clean is safe if called with a valid bitset
-        #[allow(unsafe_code)]
-        unsafe {
-            self.entries.clean(&self.bitset);
+        // Entries are removed one by one because `clean` doesn't shrink
+        // a `DenseVecStorage` which would grow every time this is reused
+        for id in &self.bitset {
+            // SAFETY: Only ids that are in the bitset are removed
+            #[allow(unsafe_code)]
+            unsafe {
+                self.entries.remove(id);
+            }
         }
         self.bitset.clear();
     }
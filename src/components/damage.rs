@@ -1,22 +1,158 @@
-use amethyst::ecs::prelude::{Component, DenseVecStorage};
+use amethyst::ecs::prelude::{Component, DenseVecStorage, Entity};
+use serde::{Deserialize, Serialize};
+
+/// The different types of damage that can be resisted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DamageType {
+    Slash,
+    Blunt,
+    /// Dealt by touching an enemy
+    Contact,
+}
 
 /// This component is paired with a collider and inflicts
 /// damage on anything that damages any entity
 /// with a health component and a collider
 pub struct Damage {
     pub amount: f32,
+    pub kind: DamageType,
+    /// Speed the target is knocked away from the source with, which dies down quickly
+    /// Only affects entities that have a velocity
+    pub knockback: f32,
 }
 
 impl Component for Damage {
     type Storage = DenseVecStorage<Self>;
 }
 
+/// Remembers which entities an attack has already hit
+///
+/// Attacks with a hit list damage every target at most once no matter how
+/// many frames they overlap for. Without one damage is dealt every frame
+pub struct HitList {
+    hit: Vec<Entity>,
+    /// Most targets that can be hit, `None` for as many as it touches
+    pub max_targets: Option<usize>,
+}
+
+impl HitList {
+    pub const fn new(max_targets: Option<usize>) -> Self {
+        Self {
+            hit: Vec::new(),
+            max_targets,
+        }
+    }
+
+    pub fn has_hit(&self, entity: Entity) -> bool {
+        self.hit.contains(&entity)
+    }
+
+    /// True once the attack has hit as many targets as it can
+    pub fn is_exhausted(&self) -> bool {
+        matches!(self.max_targets, Some(max_targets) if self.hit.len() >= max_targets)
+    }
+
+    pub fn add(&mut self, entity: Entity) {
+        self.hit.push(entity);
+    }
+}
+
+impl Component for HitList {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// The entity that made an attack or fired a projectile
+///
+/// Used to give credit for hits, like which player scores for them
+pub struct Owner {
+    pub entity: Entity,
+}
+
+impl Component for Owner {
+    type Storage = DenseVecStorage<Self>;
+}
+
 /// Amount of health an entity has
 #[derive(Clone)]
 pub struct Health {
     pub amount: f32,
+    /// Healing can't go above this
+    pub max: f32,
+}
+
+impl Health {
+    /// Full health
+    pub const fn new(max: f32) -> Self {
+        Self { amount: max, max }
+    }
 }
 
 impl Component for Health {
     type Storage = DenseVecStorage<Self>;
 }
+
+/// How much of each type of damage is ignored
+///
+/// 0.0 takes the full damage and 1.0 is immune, negative values
+/// are weaknesses that increase the damage taken
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Resistances {
+    pub slash: f32,
+    pub blunt: f32,
+    pub contact: f32,
+}
+
+impl Resistances {
+    pub const fn get(&self, kind: DamageType) -> f32 {
+        match kind {
+            DamageType::Slash => self.slash,
+            DamageType::Blunt => self.blunt,
+            DamageType::Contact => self.contact,
+        }
+    }
+
+    /// Damage left over after resisting
+    pub fn apply(&self, amount: f32, kind: DamageType) -> f32 {
+        amount * (1.0 - self.get(kind))
+    }
+}
+
+impl Component for Resistances {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Which side an entity is on
+///
+/// Damage is never dealt to an entity on the same side as the source
+/// Entities without a faction can be damaged by anything
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Faction {
+    Player,
+    Enemy,
+}
+
+impl Component for Faction {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Entities with this component can't be damaged for `duration`
+/// seconds after they were last damaged
+pub struct Invulnerability {
+    pub duration: f64,
+    /// Time until the entity can be damaged again
+    pub until: f64,
+}
+
+impl Invulnerability {
+    pub const fn new(duration: f64) -> Self {
+        Self {
+            duration,
+            until: 0.0,
+        }
+    }
+}
+
+impl Component for Invulnerability {
+    type Storage = DenseVecStorage<Self>;
+}
@@ -1,39 +1,198 @@
-use super::{CircleCollider, Health, Velocity};
+use super::{
+    BehaviorMode, CircleCollider, Damage, DamageType, EnemyBehavior, Faction, Health,
+    InflictsStatus, Resistances, StatusEffect, Velocity,
+};
 use amethyst::{
-    core::{math::Vector3, transform::Transform},
+    core::{
+        math::{Vector2, Vector3},
+        transform::Transform,
+    },
     ecs::{
-        prelude::{Component, NullStorage},
-        World,
+        prelude::{Builder, Component, DenseVecStorage},
+        World, WorldExt,
     },
-    prelude::*,
+    renderer::palette::Srgba,
 };
 use log::trace;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub struct Enemy {
+    /// Score for killing this enemy
+    pub score: u32,
+    /// Experience for killing this enemy
+    pub xp: u32,
+    pub color: Srgba,
+}
 
-#[derive(Default)]
-pub struct Enemy;
 impl Component for Enemy {
-    type Storage = NullStorage<Self>;
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Stats for a type of enemy
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EnemyKind {
+    pub health: f32,
+    pub radius: f32,
+    pub speed: f32,
+    /// Only used by enemies with a behavior
+    /// Defaults to `speed` so that full speed is reached in a second
+    #[serde(default)]
+    pub acceleration: Option<f32>,
+    pub contact_damage: f32,
+    pub score: u32,
+    #[serde(default)]
+    pub xp: u32,
+    /// RGBA
+    pub color: [f32; 4],
+    /// Enemies without a behavior just drift in a straight line
+    #[serde(default)]
+    pub behavior: Option<BehaviorMode>,
+    #[serde(default)]
+    pub split: Option<Split>,
+    #[serde(default)]
+    pub resistances: Resistances,
+    /// Status effects given to anything the enemy touches
+    #[serde(default)]
+    pub inflicts: Vec<StatusEffect>,
+}
+
+/// How an enemy splits into smaller enemies when it dies
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Split {
+    /// Number of enemies to split into
+    pub count: usize,
+    /// Multiplier for the radius and health of the smaller enemies
+    pub scale: f32,
+    /// How many times the enemies can keep splitting
+    pub generations: u32,
+}
+
+impl EnemyKind {
+    /// The kind of enemy this one splits into
+    pub fn split_kind(&self) -> Option<Self> {
+        let split = self.split.as_ref()?;
+        let mut kind = self.clone();
+        kind.radius *= split.scale;
+        kind.health *= split.scale;
+        kind.split = if split.generations > 1 {
+            Some(Split {
+                generations: split.generations - 1,
+                ..split.clone()
+            })
+        } else {
+            None
+        };
+
+        Some(kind)
+    }
+}
+
+/// Spawns smaller enemies when this entity dies
+///
+/// Holds the kind of the enemy so that the smaller enemies can be
+/// made from it after it is gone
+pub struct SplitOnDeath {
+    pub parent: EnemyKind,
+}
+
+impl Component for SplitOnDeath {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Every type of enemy by name
+///
+/// Loaded from `config/enemies.ron`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EnemyCatalogue {
+    pub kinds: BTreeMap<String, EnemyKind>,
 }
 
+impl EnemyCatalogue {
+    pub fn get(&self, name: &str) -> Option<&EnemyKind> {
+        self.kinds.get(name)
+    }
+}
+
+/// Spawns an enemy of `kind` moving in `direction`
+///
+/// The velocity is `direction * kind.speed` so `direction` doesn't have to be normalized
 pub fn spawn_enemy(
     world: &mut World,
+    kind: &EnemyKind,
     enemy_x: f32,
     enemy_y: f32,
-    x_vel: f32,
-    y_vel: f32,
-    radius: f32,
+    direction: Vector2<f32>,
 ) {
+    build_enemy(
+        world.create_entity(),
+        kind,
+        Vector3::new(enemy_x, enemy_y, 0.0),
+        direction,
+    )
+    .build();
+}
+
+/// Adds all the components of an enemy to a builder
+///
+/// Works with both the world and `LazyUpdate` so enemies can be
+/// spawned from inside systems too
+pub fn build_enemy<B: Builder>(
+    builder: B,
+    kind: &EnemyKind,
+    position: Vector3<f32>,
+    direction: Vector2<f32>,
+) -> B {
     trace!("Spawning an enemy");
-    let mut t = Transform::default();
-    t.set_translation_xyz(enemy_x, enemy_y, 0.0);
-    world
-        .create_entity()
-        .with(CircleCollider { radius })
-        .with(Enemy)
-        .with(Velocity {
-            value: Vector3::new(x_vel, y_vel, 0.0),
+    let t = Transform::from(position);
+    let [red, green, blue, alpha] = kind.color;
+    let mut builder = builder
+        .with(CircleCollider {
+            radius: kind.radius,
         })
-        .with(Health { amount: 10.0 })
-        .with(t)
-        .build();
+        .with(Enemy {
+            score: kind.score,
+            xp: kind.xp,
+            color: Srgba::new(red, green, blue, alpha),
+        })
+        .with(Velocity::new(
+            Vector3::new(direction.x, direction.y, 0.0) * kind.speed,
+        ))
+        .with(Health::new(kind.health))
+        .with(Faction::Enemy)
+        .with(t);
+
+    // Harmless enemies are left out of the damage system
+    if kind.contact_damage > 0.0 {
+        builder = builder.with(Damage {
+            amount: kind.contact_damage,
+            kind: DamageType::Contact,
+            knockback: 0.0,
+        });
+    }
+
+    if kind.resistances != Resistances::default() {
+        builder = builder.with(kind.resistances.clone());
+    }
+
+    if let Some(mode) = kind.behavior {
+        let mut behavior =
+            EnemyBehavior::new(mode, kind.speed, kind.acceleration.unwrap_or(kind.speed));
+        behavior.wander_angle = direction.y.atan2(direction.x);
+        builder = builder.with(behavior);
+    }
+
+    if !kind.inflicts.is_empty() {
+        builder = builder.with(InflictsStatus {
+            effects: kind.inflicts.clone(),
+        });
+    }
+
+    if kind.split.is_some() {
+        builder = builder.with(SplitOnDeath {
+            parent: kind.clone(),
+        });
+    }
+
+    builder
 }
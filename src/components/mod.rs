@@ -1,13 +1,23 @@
+pub mod behavior;
 pub mod colliders;
 pub mod damage;
 pub mod enemy;
 pub mod markers;
+pub mod pickup;
 pub mod player;
+pub mod status;
+pub mod upgrade;
 pub mod velocity;
+pub mod weapon;
 
+pub use self::behavior::*;
 pub use self::colliders::*;
 pub use self::damage::*;
 pub use self::enemy::*;
 pub use self::markers::*;
+pub use self::pickup::*;
 pub use self::player::*;
+pub use self::status::*;
+pub use self::upgrade::*;
 pub use self::velocity::*;
+pub use self::weapon::*;
@@ -0,0 +1,150 @@
+use super::{CircleCollider, KillAfterTime, StatusEffect};
+use amethyst::{
+    core::{math::Vector3, transform::Transform, Time},
+    ecs::{
+        prelude::{Builder, Component, DenseVecStorage},
+        World, WorldExt,
+    },
+    renderer::palette::Srgba,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Something a pickup does to the player that collects it
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PickupEffect {
+    /// Restores health up to `Health::max`
+    Heal { amount: f32 },
+    /// Multiplies how quickly the player can attack and shoot
+    AttackSpeed { multiplier: f32, duration: f64 },
+    /// Multiplies the size of attack hitboxes
+    HitboxSize { multiplier: f32, duration: f64 },
+    /// Multiplies the score the player gets
+    ScoreMultiplier { multiplier: f32, duration: f64 },
+    /// Gives the player a status effect like `Shield` or `Regen`
+    Status(StatusEffect),
+}
+
+/// Stats for a type of pickup
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PickupKind {
+    pub radius: f32,
+    /// RGBA
+    pub color: [f32; 4],
+    /// Seconds before the pickup disappears, forever if not set
+    #[serde(default)]
+    pub lifetime: Option<f64>,
+    pub effects: Vec<PickupEffect>,
+}
+
+/// A chance for a pickup to drop when an enemy is killed
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PickupDrop {
+    /// Name of the pickup kind
+    pub pickup: String,
+    /// Between 0 and 1
+    pub chance: f32,
+}
+
+/// Every type of pickup by name and what enemies drop
+///
+/// Loaded from `config/pickups.ron`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PickupCatalogue {
+    pub kinds: BTreeMap<String, PickupKind>,
+    /// Rolled in order when an enemy is killed, at most one pickup drops
+    #[serde(default)]
+    pub drops: Vec<PickupDrop>,
+}
+
+impl PickupCatalogue {
+    pub fn get(&self, name: &str) -> Option<&PickupKind> {
+        self.kinds.get(name)
+    }
+}
+
+/// Players that touch this get its effects and it disappears
+pub struct Pickup {
+    pub effects: Vec<PickupEffect>,
+    pub color: Srgba,
+}
+
+impl Component for Pickup {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// A multiplier that only lasts for a while
+#[derive(Clone, Copy, Debug)]
+pub struct Buff {
+    pub multiplier: f32,
+    pub until: f64,
+}
+
+impl Buff {
+    pub const fn new(multiplier: f32, until: f64) -> Self {
+        Self { multiplier, until }
+    }
+
+    /// The multiplier while it lasts and 1 after
+    pub fn get(&self, now: f64) -> f32 {
+        if now < self.until {
+            self.multiplier
+        } else {
+            1.0
+        }
+    }
+}
+
+impl Default for Buff {
+    fn default() -> Self {
+        Self::new(1.0, 0.0)
+    }
+}
+
+/// Temporary boosts a player got from pickups
+#[derive(Clone, Debug, Default)]
+pub struct Buffs {
+    pub attack_speed: Buff,
+    pub hitbox_size: Buff,
+    pub score: Buff,
+}
+
+impl Component for Buffs {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Spawns a pickup of `kind` at a position
+pub fn spawn_pickup(world: &mut World, kind: &PickupKind, x: f32, y: f32) {
+    let now = world.read_resource::<Time>().absolute_time_seconds();
+    build_pickup(world.create_entity(), kind, Vector3::new(x, y, 0.0), now).build();
+}
+
+/// Adds all the components of a pickup to a builder
+///
+/// Works with both the world and `LazyUpdate` so pickups can be
+/// dropped from inside systems too
+pub fn build_pickup<B: Builder>(
+    builder: B,
+    kind: &PickupKind,
+    position: Vector3<f32>,
+    now: f64,
+) -> B {
+    let [red, green, blue, alpha] = kind.color;
+    let builder = builder
+        .with(CircleCollider {
+            radius: kind.radius,
+        })
+        .with(Pickup {
+            effects: kind.effects.clone(),
+            color: Srgba::new(red, green, blue, alpha),
+        })
+        .with(Transform::from(position));
+
+    if let Some(lifetime) = kind.lifetime {
+        builder.with(KillAfterTime {
+            time: now + lifetime,
+        })
+    } else {
+        builder
+    }
+}
@@ -1,31 +1,122 @@
-use amethyst::{core::transform::Transform, ecs::prelude::*};
+use amethyst::{
+    core::{
+        math::{Vector2, Vector3},
+        transform::Transform,
+    },
+    ecs::prelude::*,
+};
+use serde::{Deserialize, Serialize};
 
-use crate::components::colliders;
+use crate::components::{
+    colliders, Buffs, Combo, Faction, Health, Invulnerability, Projectile, Weapon,
+};
+use crate::input::bindings::PlayerId;
+
+/// Stats for the player
+///
+/// Loaded from `config/player.ron`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PlayerConfig {
+    pub speed: f32,
+    pub radius: f32,
+    pub health: f32,
+    /// Time in seconds that the player can't be hurt after taking damage
+    pub invulnerability: f64,
+    /// Weapons the player starts with, starting with the first
+    pub weapons: Vec<Weapon>,
+    /// The player can't shoot without this
+    #[serde(default)]
+    pub projectile: Option<Projectile>,
+    /// The player can't dash without this
+    #[serde(default)]
+    pub dash: Option<DashConfig>,
+}
+
+/// A short burst of speed in the direction the player is moving
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DashConfig {
+    pub speed: f32,
+    /// Seconds the dash lasts
+    pub duration: f64,
+    /// Seconds from the start of a dash until the next one
+    pub cooldown: f64,
+    /// Whether the player can't be hurt while dashing
+    #[serde(default)]
+    pub invulnerable: bool,
+}
 
 /// Component that represents a player controlled entity
 /// Uses the x and y axes defined in the bindings config for movement
 ///
+/// id picks which set of bindings controls the player
 /// speed is the entities movement speed
 /// nextAttack is the next time the player can attack, used for attack cooldown
+/// facing is the normalized direction attacks are made in
+/// weapons starts as `PlayerConfig::weapons` and changes with upgrades
+/// weapon is the index of the current weapon in weapons
+/// projectile starts as `PlayerConfig::projectile` and changes with upgrades
+/// nextShot and nextDash work like nextAttack for shooting and dashing
+/// dashUntil is when the current dash ends
+/// chargedRelease is how long attack was held if it was let go during the
+/// attack cooldown, the charged attack is made once the cooldown is over
 pub struct Player {
+    pub id: PlayerId,
     pub speed: f32,
     pub next_attack: f64,
+    pub next_shot: f64,
+    pub next_dash: f64,
+    pub dash_until: f64,
+    pub dash_direction: Vector2<f32>,
+    pub facing: Vector2<f32>,
+    pub weapons: Vec<Weapon>,
+    pub weapon: usize,
+    pub projectile: Option<Projectile>,
+    pub combo: Combo,
+    pub charged_release: Option<f64>,
 }
 
 impl Component for Player {
     type Storage = HashMapStorage<Self>;
 }
 
-pub fn initialize_player(world: &mut World) {
-    let transform = Transform::default();
+/// Spawns `count` players next to each other, each with their own bindings
+pub fn initialize_players(world: &mut World, count: usize) {
+    let config = (*world.read_resource::<PlayerConfig>()).clone();
+
+    for id in 0..count {
+        initialize_player(world, &config, id);
+    }
+}
+
+fn initialize_player(world: &mut World, config: &PlayerConfig, id: PlayerId) {
+    // Leave a gap between players so that they don't start on top of each other
+    #[allow(clippy::cast_precision_loss)]
+    let transform = Transform::from(Vector3::new(id as f32 * config.radius * 4.0, 0.0, 0.0));
 
     world
         .create_entity()
         .with(transform)
-        .with(colliders::CircleCollider { radius: 5.0 })
+        .with(colliders::CircleCollider {
+            radius: config.radius,
+        })
         .with(Player {
-            speed: 50.0,
+            id,
+            speed: config.speed,
             next_attack: 0.0,
+            next_shot: 0.0,
+            next_dash: 0.0,
+            dash_until: 0.0,
+            dash_direction: Vector2::zeros(),
+            facing: Vector2::y(),
+            weapons: config.weapons.clone(),
+            weapon: 0,
+            projectile: config.projectile.clone(),
+            combo: Combo::default(),
+            charged_release: None,
         })
+        .with(Health::new(config.health))
+        .with(Invulnerability::new(config.invulnerability))
+        .with(Buffs::default())
+        .with(Faction::Player)
         .build();
 }
@@ -0,0 +1,198 @@
+//! Timed effects that change how an entity moves, attacks or takes damage
+//!
+//! Every effect lasts until an absolute time like `KillAfterTime` and is
+//! removed by the `StatusEffectSystem` once that time has passed.
+//! Applying an effect the entity already has replaces it
+#![allow(clippy::type_repetition_in_bounds)]
+
+use amethyst::ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Loses `damage_per_second` health over time
+pub struct Poison {
+    pub damage_per_second: f32,
+    pub until: f64,
+}
+
+impl Component for Poison {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Moves at `multiplier` times the usual speed
+pub struct Slow {
+    pub multiplier: f32,
+    pub until: f64,
+}
+
+impl Component for Slow {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Can't move, attack or deal contact damage
+pub struct Stun {
+    pub until: f64,
+}
+
+impl Component for Stun {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Absorbs damage before it reaches health
+///
+/// Removed early once `amount` runs out
+pub struct Shield {
+    pub amount: f32,
+    pub until: f64,
+}
+
+impl Shield {
+    /// Takes as much of `damage` as it can and returns the rest
+    pub fn absorb(&mut self, damage: f32) -> f32 {
+        let absorbed = damage.min(self.amount).max(0.0);
+        self.amount -= absorbed;
+        damage - absorbed
+    }
+}
+
+impl Component for Shield {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Gains `health_per_second` health over time up to `Health::max`
+pub struct Regen {
+    pub health_per_second: f32,
+    pub until: f64,
+}
+
+impl Component for Regen {
+    type Storage = DenseVecStorage<Self>;
+}
+
+/// Anything with an expiry time, used to remove effects generically
+pub trait Expires {
+    fn until(&self) -> f64;
+}
+
+macro_rules! impl_expires {
+    ($($effect:ty),*) => {
+        $(impl Expires for $effect {
+            fn until(&self) -> f64 {
+                self.until
+            }
+        })*
+    };
+}
+
+impl_expires!(Poison, Slow, Stun, Shield, Regen);
+
+/// A status effect as written in config files
+///
+/// Durations are in seconds from when the effect is applied
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum StatusEffect {
+    Poison {
+        damage_per_second: f32,
+        duration: f64,
+    },
+    Slow {
+        multiplier: f32,
+        duration: f64,
+    },
+    Stun {
+        duration: f64,
+    },
+    Shield {
+        amount: f32,
+        duration: f64,
+    },
+    Regen {
+        health_per_second: f32,
+        duration: f64,
+    },
+}
+
+/// Write access to every status effect storage
+#[derive(SystemData)]
+pub struct StatusEffects<'a> {
+    pub poisons: WriteStorage<'a, Poison>,
+    pub slows: WriteStorage<'a, Slow>,
+    pub stuns: WriteStorage<'a, Stun>,
+    pub shields: WriteStorage<'a, Shield>,
+    pub regens: WriteStorage<'a, Regen>,
+}
+
+impl StatusEffect {
+    /// Gives `entity` this effect starting at `now`
+    pub fn apply(self, entity: Entity, now: f64, effects: &mut StatusEffects) {
+        // Inserting only fails for dead entities which don't need effects
+        let _ = match self {
+            Self::Poison {
+                damage_per_second,
+                duration,
+            } => effects
+                .poisons
+                .insert(
+                    entity,
+                    Poison {
+                        damage_per_second,
+                        until: now + duration,
+                    },
+                )
+                .map(drop),
+            Self::Slow {
+                multiplier,
+                duration,
+            } => effects
+                .slows
+                .insert(
+                    entity,
+                    Slow {
+                        multiplier,
+                        until: now + duration,
+                    },
+                )
+                .map(drop),
+            Self::Stun { duration } => effects
+                .stuns
+                .insert(
+                    entity,
+                    Stun {
+                        until: now + duration,
+                    },
+                )
+                .map(drop),
+            Self::Shield { amount, duration } => effects
+                .shields
+                .insert(
+                    entity,
+                    Shield {
+                        amount,
+                        until: now + duration,
+                    },
+                )
+                .map(drop),
+            Self::Regen {
+                health_per_second,
+                duration,
+            } => effects
+                .regens
+                .insert(
+                    entity,
+                    Regen {
+                        health_per_second,
+                        until: now + duration,
+                    },
+                )
+                .map(drop),
+        };
+    }
+}
+
+/// Status effects given to whatever this entity damages
+pub struct InflictsStatus {
+    pub effects: Vec<StatusEffect>,
+}
+
+impl Component for InflictsStatus {
+    type Storage = DenseVecStorage<Self>;
+}
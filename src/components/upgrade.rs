@@ -0,0 +1,107 @@
+use super::{AttackMove, Player, Weapon};
+use serde::{Deserialize, Serialize};
+
+/// What an upgrade does to a player
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum UpgradeEffect {
+    /// Multiplies the damage of every move of every weapon and of the projectile
+    Damage { multiplier: f32 },
+    /// Multiplies the cooldown of every move of every weapon and of the projectile
+    Cooldown { multiplier: f64 },
+    /// Multiplies movement speed
+    Speed { multiplier: f32 },
+    /// Multiplies the hitbox size of every move of every weapon and of the projectile
+    HitboxSize { multiplier: f32 },
+    /// Adds a weapon to switch to
+    Weapon(Weapon),
+}
+
+/// An upgrade that can be picked when levelling up
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Upgrade {
+    pub name: String,
+    pub effect: UpgradeEffect,
+}
+
+impl Upgrade {
+    pub fn apply(&self, player: &mut Player) {
+        match &self.effect {
+            UpgradeEffect::Damage { multiplier } => {
+                for_each_move(player, |attack| attack.damage *= multiplier);
+                if let Some(projectile) = &mut player.projectile {
+                    projectile.damage *= multiplier;
+                }
+            }
+            UpgradeEffect::Cooldown { multiplier } => {
+                for_each_move(player, |attack| attack.cooldown *= multiplier);
+                if let Some(projectile) = &mut player.projectile {
+                    projectile.cooldown *= multiplier;
+                }
+            }
+            UpgradeEffect::HitboxSize { multiplier } => {
+                for_each_move(player, |attack| {
+                    attack.hitbox = attack.hitbox.scaled(*multiplier);
+                });
+                if let Some(projectile) = &mut player.projectile {
+                    projectile.radius *= multiplier;
+                }
+            }
+            UpgradeEffect::Speed { multiplier } => player.speed *= multiplier,
+            UpgradeEffect::Weapon(weapon) => {
+                if self.is_useful_for(player) {
+                    player.weapons.push(weapon.clone());
+                }
+            }
+        }
+    }
+
+    /// Weapon upgrades are only useful to players that don't have the weapon yet
+    pub fn is_useful_for(&self, player: &Player) -> bool {
+        match &self.effect {
+            UpgradeEffect::Weapon(weapon) => {
+                player.weapons.iter().all(|owned| owned.name != weapon.name)
+            }
+            _ => true,
+        }
+    }
+}
+
+fn for_each_move(player: &mut Player, f: impl FnMut(&mut AttackMove)) {
+    player
+        .weapons
+        .iter_mut()
+        .flat_map(|weapon| &mut weapon.moves)
+        .for_each(f);
+}
+
+/// How much experience each level takes
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Levels {
+    /// Experience to get from level 0 to level 1
+    pub first: u32,
+    /// Every level takes this many times more experience than the last
+    pub growth: f32,
+}
+
+impl Levels {
+    /// Experience needed to go from `level` to the next one
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    pub fn xp_to_next(&self, level: u32) -> u32 {
+        let needed = self.first as f32 * self.growth.powi(level as i32);
+        (needed as u32).max(1)
+    }
+}
+
+/// Every upgrade that can be offered when levelling up
+///
+/// Loaded from `config/upgrades.ron`
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct UpgradeTable {
+    pub levels: Levels,
+    pub upgrades: Vec<Upgrade>,
+}
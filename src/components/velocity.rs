@@ -3,6 +3,17 @@ use amethyst::ecs::prelude::*;
 
 pub struct Velocity {
     pub value: Vector3<f32>,
+    /// Extra speed from being hit that dies down over time
+    pub knockback: Vector3<f32>,
+}
+
+impl Velocity {
+    pub fn new(value: Vector3<f32>) -> Self {
+        Self {
+            value,
+            knockback: Vector3::zeros(),
+        }
+    }
 }
 
 impl Component for Velocity {
@@ -0,0 +1,318 @@
+use super::{
+    BoxCollider, CircleCollider, Damage, DamageType, Faction, HitList, KillAfterCollision,
+    KillAfterTime, Velocity,
+};
+use amethyst::{
+    core::{
+        math::{Vector2, Vector3},
+        transform::Transform,
+    },
+    ecs::prelude::Builder,
+};
+use serde::{Deserialize, Serialize};
+
+/// Shape of the collider an attack makes
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Hitbox {
+    Box { width: f32, height: f32 },
+    Circle { radius: f32 },
+}
+
+impl Hitbox {
+    pub fn scaled(&self, scale: f32) -> Self {
+        match *self {
+            Self::Box { width, height } => Self::Box {
+                width: width * scale,
+                height: height * scale,
+            },
+            Self::Circle { radius } => Self::Circle {
+                radius: radius * scale,
+            },
+        }
+    }
+}
+
+/// One attack in a weapon's combo chain
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AttackMove {
+    pub damage: f32,
+    #[serde(default)]
+    pub knockback: f32,
+    /// Seconds until the next attack can be made
+    pub cooldown: f64,
+    /// Seconds the hitbox stays out for
+    pub lifetime: f64,
+    pub hitbox: Hitbox,
+    /// How far in front of the player the hitbox is
+    pub offset: f32,
+}
+
+impl AttackMove {
+    /// Multiplies the damage by `damage` and the hitbox size by `size`
+    pub fn scaled(&self, damage: f32, size: f32) -> Self {
+        Self {
+            damage: self.damage * damage,
+            hitbox: self.hitbox.scaled(size),
+            ..self.clone()
+        }
+    }
+}
+
+/// Holding attack and letting go makes a stronger version of the first move
+///
+/// The multipliers grow from 1 at `min_time` up to their full value at `max_time`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Charge {
+    /// Seconds attack has to be held for to charge at all
+    pub min_time: f64,
+    /// Seconds after which holding longer doesn't do anything
+    pub max_time: f64,
+    pub damage_multiplier: f32,
+    pub size_multiplier: f32,
+}
+
+/// Stats for a weapon the player can switch to
+///
+/// Loaded as part of `config/player.ron`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Weapon {
+    pub name: String,
+    pub kind: DamageType,
+    /// Seconds after an attack's cooldown that attacking again continues the combo
+    #[serde(default)]
+    pub combo_window: f64,
+    /// The combo chain, a weapon with one move just repeats it
+    pub moves: Vec<AttackMove>,
+    /// Weapons without a charge keep attacking while attack is held
+    #[serde(default)]
+    pub charge: Option<Charge>,
+}
+
+impl Weapon {
+    /// The attack made by letting go of attack after holding it for `held` seconds
+    ///
+    /// `None` if the weapon can't be charged or it wasn't held long enough
+    #[allow(clippy::cast_possible_truncation)] // Charge is between 0 and 1
+    pub fn charged_move(&self, held: f64) -> Option<AttackMove> {
+        let charge = self.charge.as_ref()?;
+        if held < charge.min_time {
+            return None;
+        }
+
+        let charge_time = (charge.max_time - charge.min_time).max(f64::EPSILON);
+        let amount = ((held - charge.min_time) / charge_time).min(1.0) as f32;
+        self.moves.first().map(|attack| {
+            attack.scaled(
+                1.0 + amount * (charge.damage_multiplier - 1.0),
+                1.0 + amount * (charge.size_multiplier - 1.0),
+            )
+        })
+    }
+}
+
+/// Where the player is in their current weapon's combo chain
+#[derive(Clone, Debug, Default)]
+pub struct Combo {
+    /// Index of the last move made, `None` when not in a combo
+    last: Option<usize>,
+    /// Time after which the next attack starts the chain over
+    expires: f64,
+}
+
+impl Combo {
+    /// Picks the move for an attack made at `now` and moves along the chain
+    ///
+    /// Attacking before the combo window runs out continues the chain,
+    /// which starts over after the last move or once the window is missed
+    pub fn advance<'w>(&mut self, weapon: &'w Weapon, now: f64) -> Option<&'w AttackMove> {
+        let step = match self.last {
+            Some(last) if now <= self.expires => (last + 1) % weapon.moves.len().max(1),
+            _ => 0,
+        };
+        let attack = weapon.moves.get(step)?;
+
+        self.last = Some(step);
+        self.expires = now + attack.cooldown + weapon.combo_window;
+        Some(attack)
+    }
+
+    /// Drops out of the chain, the next attack is the first move
+    pub const fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+/// Adds all the components of an attack to a builder
+///
+/// The attack is placed in front of `facing` so the builder should
+/// also be given a `Parent` for it to follow the attacker
+pub fn build_attack<B: Builder>(
+    builder: B,
+    kind: DamageType,
+    attack: &AttackMove,
+    facing: Vector2<f32>,
+    now: f64,
+) -> B {
+    // Colliders are axis aligned so the rotation doesn't change what it
+    // hits yet but it already points the right way for when they aren't
+    let offset = facing * attack.offset;
+    let mut transform = Transform::from(Vector3::new(offset.x, offset.y, 0.0));
+    transform.set_rotation_2d(facing.y.atan2(facing.x) - std::f32::consts::FRAC_PI_2);
+
+    let builder = match attack.hitbox {
+        Hitbox::Box { width, height } => builder.with(BoxCollider { width, height }),
+        Hitbox::Circle { radius } => builder.with(CircleCollider { radius }),
+    };
+
+    builder
+        .with(transform)
+        .with(Damage {
+            amount: attack.damage,
+            kind,
+            knockback: attack.knockback,
+        })
+        .with(Faction::Player)
+        // Attacks cut through everything they touch but
+        // only hurt each of them once
+        .with(HitList::new(None))
+        .with(KillAfterTime {
+            time: now + attack.lifetime,
+        })
+}
+
+/// A shot the player fires in the direction they are facing
+///
+/// Loaded as part of `config/player.ron`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Projectile {
+    pub damage: f32,
+    pub kind: DamageType,
+    #[serde(default)]
+    pub knockback: f32,
+    pub speed: f32,
+    pub radius: f32,
+    /// Seconds until the next shot can be fired
+    pub cooldown: f64,
+    /// How far it goes before disappearing
+    pub range: f32,
+    /// How many enemies it goes through, 0 stops at the first one
+    #[serde(default)]
+    pub pierce: usize,
+}
+
+/// Adds all the components of a projectile to a builder
+///
+/// Projectiles are ordinary damage sources that die when they collide
+/// so the damage and kill after systems handle them. The hit list lets it
+/// go through `pierce` targets first and the lifetime is however long it
+/// takes to travel its range
+pub fn build_projectile<B: Builder>(
+    builder: B,
+    projectile: &Projectile,
+    position: Vector3<f32>,
+    direction: Vector2<f32>,
+    now: f64,
+) -> B {
+    builder
+        .with(CircleCollider {
+            radius: projectile.radius,
+        })
+        .with(Transform::from(position))
+        .with(Velocity::new(
+            Vector3::new(direction.x, direction.y, 0.0) * projectile.speed,
+        ))
+        .with(Damage {
+            amount: projectile.damage,
+            kind: projectile.kind,
+            knockback: projectile.knockback,
+        })
+        .with(Faction::Player)
+        .with(HitList::new(Some(projectile.pierce + 1)))
+        .with(KillAfterCollision)
+        .with(KillAfterTime {
+            time: now + f64::from(projectile.range / projectile.speed),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weapon() -> Weapon {
+        let attack = |damage| AttackMove {
+            damage,
+            knockback: 0.0,
+            cooldown: 1.0,
+            lifetime: 0.1,
+            hitbox: Hitbox::Circle { radius: 1.0 },
+            offset: 0.0,
+        };
+
+        Weapon {
+            name: "test".to_string(),
+            kind: DamageType::Slash,
+            combo_window: 0.5,
+            moves: vec![attack(1.0), attack(2.0), attack(3.0)],
+            charge: Some(Charge {
+                min_time: 0.5,
+                max_time: 1.5,
+                damage_multiplier: 3.0,
+                size_multiplier: 2.0,
+            }),
+        }
+    }
+
+    // Index of the move used for an attack at `now`
+    fn step(combo: &mut Combo, weapon: &Weapon, now: f64) -> Option<usize> {
+        combo.advance(weapon, now)?;
+        combo.last
+    }
+
+    #[test]
+    fn chains_within_window_and_wraps() {
+        let weapon = weapon();
+        let mut combo = Combo::default();
+
+        assert_eq!(step(&mut combo, &weapon, 0.0), Some(0));
+        assert_eq!(step(&mut combo, &weapon, 1.2), Some(1));
+        assert_eq!(step(&mut combo, &weapon, 2.4), Some(2));
+        assert_eq!(step(&mut combo, &weapon, 3.6), Some(0));
+    }
+
+    #[test]
+    fn resets_after_window() {
+        let weapon = weapon();
+        let mut combo = Combo::default();
+
+        assert_eq!(step(&mut combo, &weapon, 0.0), Some(0));
+        assert_eq!(step(&mut combo, &weapon, 1.0), Some(1));
+        // Cooldown of 1 and window of 0.5 ends at 2.5
+        assert_eq!(step(&mut combo, &weapon, 2.6), Some(0));
+
+        combo.reset();
+        assert_eq!(step(&mut combo, &weapon, 2.7), Some(0));
+    }
+
+    #[test]
+    fn no_moves_no_attack() {
+        let weapon = Weapon {
+            moves: Vec::new(),
+            ..weapon()
+        };
+        assert!(Combo::default().advance(&weapon, 0.0).is_none());
+    }
+
+    #[test]
+    fn charge_scales_up_to_cap() {
+        let weapon = weapon();
+        let damage = |held| weapon.charged_move(held).map(|attack| attack.damage);
+
+        assert!(damage(0.4).is_none());
+        let weak = damage(0.5).unwrap();
+        let half = damage(1.0).unwrap();
+        let full = damage(1.5).unwrap();
+        assert!(weak < half && half < full);
+        assert!((full - 3.0).abs() < f32::EPSILON);
+        assert!((damage(10.0).unwrap() - full).abs() < f32::EPSILON);
+    }
+}
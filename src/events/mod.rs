@@ -0,0 +1,28 @@
+//! Events that are published on an `EventChannel` for other systems to react to
+use amethyst::{core::math::Vector3, ecs::Entity};
+
+/// Why an entity died
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathCause {
+    /// Ran out of health
+    Damage,
+    /// Had `KillAfterCollision` and collided with something
+    Collision,
+    /// Lived past its `KillAfterTime`
+    Timeout,
+}
+
+/// Published whenever an entity dies
+///
+/// Systems should publish this instead of deleting entities themselves
+/// so that other systems (score, splitting, effects) can react to it.
+/// The `ReaperSystem` deletes the entity at the end of the frame
+#[derive(Clone, Debug)]
+pub struct DeathEvent {
+    pub entity: Entity,
+    pub cause: DeathCause,
+    /// Where the entity was when it died
+    pub position: Vector3<f32>,
+    /// The entity that dealt the killing blow if there is one
+    pub killer: Option<Entity>,
+}
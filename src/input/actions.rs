@@ -0,0 +1,57 @@
+use super::bindings::ActionBinding;
+use std::collections::HashMap;
+
+/// What an action did this frame
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ActionState {
+    pub down: bool,
+    /// Went down this frame
+    pub pressed: bool,
+    /// Went up this frame
+    pub released: bool,
+    /// Seconds the action has been held for
+    ///
+    /// Still set on the frame it is released so the whole hold can be read
+    pub held: f64,
+}
+
+impl ActionState {
+    fn update(&mut self, down: bool, delta: f64) {
+        self.pressed = down && !self.down;
+        self.released = !down && self.down;
+        if self.pressed {
+            self.held = 0.0;
+        }
+        if down {
+            self.held += delta;
+        }
+        self.down = down;
+    }
+}
+
+/// Press and release edges for every action
+///
+/// `InputHandler` only knows whether an action is down right now.
+/// Updated every frame by the `ActionStateSystem`
+#[derive(Default)]
+pub struct ActionStates {
+    states: HashMap<ActionBinding, ActionState>,
+}
+
+impl ActionStates {
+    pub fn update(&mut self, action: &ActionBinding, down: bool, delta: f64) {
+        // Only clone the action the first time it is seen
+        if let Some(state) = self.states.get_mut(action) {
+            state.update(down, delta);
+        } else {
+            let mut state = ActionState::default();
+            state.update(down, delta);
+            self.states.insert(action.clone(), state);
+        }
+    }
+
+    /// Actions that have never been seen are up
+    pub fn get(&self, action: &ActionBinding) -> ActionState {
+        self.states.get(action).copied().unwrap_or_default()
+    }
+}
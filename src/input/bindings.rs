@@ -1,16 +1,28 @@
 use amethyst::input::BindingTypes;
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display};
+
+/// Which player a binding belongs to, starting from 0
+pub type PlayerId = usize;
+
+/// Every player has their own set of axes, written as `Horizontal(0)` in the config
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AxisBinding {
-    Horizontal,
-    Vertical,
+    Horizontal(PlayerId),
+    Vertical(PlayerId),
+    /// Aiming overrides facing the way the player is moving
+    AimHorizontal(PlayerId),
+    AimVertical(PlayerId),
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ActionBinding {
     ToggleColliders,
-    Attack,
+    Attack(PlayerId),
+    /// Cycles through the weapons in the player config
+    SwitchWeapon(PlayerId),
+    Dash(PlayerId),
+    Shoot(PlayerId),
 }
 // Necessary for bindings config file
 impl Display for AxisBinding {
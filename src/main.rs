@@ -8,10 +8,11 @@
 #![allow(clippy::module_name_repetitions)]
 
 use amethyst::audio::AudioBundle;
+use amethyst::config::Config;
 use amethyst::input::InputBundle;
 use amethyst::ui::{RenderUi, UiBundle};
 use amethyst::{
-    core::{frame_limiter::FrameRateLimitStrategy, transform::TransformBundle},
+    core::frame_limiter::FrameRateLimitStrategy,
     prelude::*,
     renderer::{
         plugins::{RenderDebugLines, RenderFlat2D, RenderToWindow},
@@ -22,6 +23,7 @@ use amethyst::{
 };
 
 mod components;
+mod events;
 mod input;
 mod scenes;
 mod states;
@@ -29,8 +31,16 @@ mod sushi_cutters;
 mod systems;
 mod util;
 
+use crate::components::{EnemyCatalogue, PickupCatalogue, PlayerConfig, UpgradeTable};
 use crate::input::bindings::InputBindingTypes;
 use crate::states::initial_state;
+use crate::util::timed::{timed, timed_desc};
+
+// Counts allocations so that benchmarks can report them
+// Also used in tests to check that hot paths don't allocate
+#[cfg(any(test, feature = "benchmark"))]
+#[global_allocator]
+static ALLOCATOR: util::alloc_counter::CountingAllocator = util::alloc_counter::CountingAllocator;
 
 fn main() -> amethyst::Result<()> {
     amethyst::start_logger(Default::default());
@@ -42,6 +52,18 @@ fn main() -> amethyst::Result<()> {
     let display_config_path = config_dir.join("display.ron");
     let bindings_path = config_dir.join("bindings.ron");
 
+    let enemies = EnemyCatalogue::load(config_dir.join("enemies.ron"))?;
+    let player = PlayerConfig::load(config_dir.join("player.ron"))?;
+    let pickups = PickupCatalogue::load(config_dir.join("pickups.ron"))?;
+    let upgrades = UpgradeTable::load(config_dir.join("upgrades.ron"))?;
+
+    #[cfg(feature = "benchmark")]
+    {
+        if let Some(scene) = util::sim_bench::get_headless_scene_cli() {
+            return util::sim_bench::run_headless(scene, enemies, player, pickups, upgrades);
+        }
+    }
+
     let input_bundle =
         InputBundle::<InputBindingTypes>::new().with_bindings_from_file(bindings_path)?;
 
@@ -57,50 +79,47 @@ fn main() -> amethyst::Result<()> {
                 .with_plugin(RenderDebugLines::default()),
         )?
         .with_bundle(input_bundle)?
-        .with(
-            systems::PlayerControlSystem,
-            "player_control",
-            &["input_system"],
-        )
-        .with(systems::VelocitySystem, "velocity_system", &[])
-        .with_bundle(TransformBundle::new().with_dep(&["player_control", "velocity_system"]))?
+        .with_bundle(systems::GameplayBundle::default().with_dep(&["input_system"]))?
         .with_bundle(UiBundle::<InputBindingTypes>::new())?
         .with_bundle(AudioBundle::default())?
-        .with(
-            systems::CollisionsSystem::default(),
-            "collisions_system",
-            &["transform_system"],
-        )
-        .with(
-            systems::BorderSystem,
-            "border_system",
-            &["transform_system"],
-        )
-        .with(
-            systems::KillAfterSystem,
-            "kill_after_system",
-            &["collisions_system"],
-        )
         .with_system_desc(
-            systems::ScoreSystemDesc,
-            "score_system",
-            &["collisions_system"],
-        )
-        .with(
-            systems::DamageSystem,
-            "damage_system",
-            &["collisions_system"],
+            timed_desc("score_text_system", systems::ScoreTextSystemDesc),
+            "score_text_system",
+            &["score_system"],
         )
         .with(
-            systems::CollisionDebugSystem,
+            timed("collision_debug", systems::CollisionDebugSystem),
             "collision_debug",
             &["collisions_system", "input_system"],
         );
 
     let mut game = Application::build(assets_dir, initial_state())?
+        .with_resource(enemies)
+        .with_resource(player)
+        .with_resource(pickups)
+        .with_resource(upgrades)
         .with_frame_limit(FrameRateLimitStrategy::Unlimited, 144)
         .build(game_data)?;
     game.run();
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::{input::Bindings, window::DisplayConfig};
+
+    // The game can't start if any of these fail to load
+    #[test]
+    fn config_files_load() {
+        let config_dir = application_root_dir().unwrap().join("config");
+
+        DisplayConfig::load(config_dir.join("display.ron")).expect("display.ron");
+        Bindings::<InputBindingTypes>::load(config_dir.join("bindings.ron")).expect("bindings.ron");
+        EnemyCatalogue::load(config_dir.join("enemies.ron")).expect("enemies.ron");
+        PlayerConfig::load(config_dir.join("player.ron")).expect("player.ron");
+        PickupCatalogue::load(config_dir.join("pickups.ron")).expect("pickups.ron");
+        UpgradeTable::load(config_dir.join("upgrades.ron")).expect("upgrades.ron");
+    }
+}
@@ -1,35 +1,73 @@
-use crate::components::{CircleCollider, Health};
+use crate::components::{
+    BehaviorMode, CircleCollider, EnemyCatalogue, EnemyKind, Health, PickupCatalogue, PickupKind,
+};
 use amethyst::{
-    core::{math::Vector3, transform::Transform},
+    core::{
+        math::{Vector2, Vector3},
+        transform::Transform,
+    },
     ecs::prelude::*,
 };
+use rand::{rngs::StdRng, SeedableRng};
 
-use crate::components::enemy;
+use crate::components::{enemy, pickup};
 use crate::sushi_cutters::{ARENA_HEIGHT, ARENA_WIDTH};
+use crate::util::env::get_variable;
 
 pub type SceneInitializer = fn(&mut World);
 
 pub struct Scene {
     pub name: &'static str,
     pub initializer: SceneInitializer,
+    /// Number of players to spawn, each controlled by their own bindings
+    pub players: usize,
 }
 
-pub const SCENES: [Scene; 4] = [
+pub const SCENES: [Scene; 9] = [
     Scene {
         name: "basic",
         initializer: initialize_raw_colliders as SceneInitializer,
+        players: 1,
     },
     Scene {
         name: "enemies",
         initializer: initialize_enemies_rand as SceneInitializer,
+        players: 1,
     },
     Scene {
         name: "enemies_bench",
         initializer: initialize_enemies_bench as SceneInitializer,
+        players: 1,
     },
     Scene {
         name: "enemies_scaled",
         initializer: initialize_enemies_scaled as SceneInitializer,
+        players: 1,
+    },
+    Scene {
+        name: "behaviors",
+        initializer: initialize_behaviors as SceneInitializer,
+        players: 1,
+    },
+    Scene {
+        name: "enemy_kinds",
+        initializer: initialize_enemy_kinds as SceneInitializer,
+        players: 1,
+    },
+    Scene {
+        name: "coop",
+        initializer: initialize_enemy_kinds as SceneInitializer,
+        players: 2,
+    },
+    Scene {
+        name: "coop_4",
+        initializer: initialize_enemy_kinds as SceneInitializer,
+        players: 4,
+    },
+    Scene {
+        name: "pickups",
+        initializer: initialize_pickups as SceneInitializer,
+        players: 1,
     },
 ];
 
@@ -37,8 +75,48 @@ pub fn get_scene(scene_name: &str) -> Option<&'static Scene> {
     SCENES.iter().find(|x| x.name == scene_name)
 }
 
+/// Parameters shared by the scene initializers
+///
+/// Read once from the environment when a scene starts so that the
+/// benchmark output can record exactly what the scene was built with
+#[derive(Clone, Debug)]
+pub struct SceneConfig {
+    pub enemy_count: usize,
+    pub area_scale: f32,
+    pub seed: u64,
+}
+
+impl SceneConfig {
+    pub fn from_env() -> Self {
+        let seed = get_variable("SEED", rand::random());
+        log::info!("Scene seed: {}", seed);
+
+        Self {
+            enemy_count: get_variable("ENEMY_COUNT", 5000),
+            area_scale: get_variable("AREA_SCALE", 0.4),
+            seed,
+        }
+    }
+
+    pub fn rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.seed)
+    }
+
+    /// A generator for a system that needs random numbers while the scene runs
+    ///
+    /// Every system gets its own stream based on its name so that the
+    /// numbers don't depend on the order that systems happen to run in
+    pub fn system_rng(&self, system: &str) -> StdRng {
+        // FNV-1a, the std hasher isn't guaranteed to be the same between releases
+        let stream = system.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+        StdRng::seed_from_u64(self.seed ^ stream)
+    }
+}
+
 fn create_test_colliders(world: &mut World, transforms: Vec<Transform>) {
-    let health = Health { amount: 10.0 };
+    let health = Health::new(10.0);
     let collider = CircleCollider {
         radius: CIRCLE_SIZE,
     };
@@ -67,22 +145,30 @@ pub fn initialize_raw_colliders(world: &mut World) {
 
 pub fn initialize_enemies_rand(world: &mut World) {
     use rand::distributions::{Distribution, Uniform};
-    let enemy_count = Uniform::new(1, 20).sample(&mut rand::thread_rng());
-    initialize_enemies(world, enemy_count, 4.0);
+    let mut rng = world.read_resource::<SceneConfig>().rng();
+    let enemy_count = Uniform::new(1, 20).sample(&mut rng);
+    let kind = drifting_kind(4.0);
+    initialize_enemies(world, &mut rng, enemy_count, &kind);
 }
 
 pub fn initialize_enemies_bench(world: &mut World) {
-    let enemy_count = get_enemy_count();
+    let (enemy_count, mut rng) = {
+        let config = world.read_resource::<SceneConfig>();
+        (config.enemy_count, config.rng())
+    };
 
     log::info!("`enemies_bench` starting with {} enemies", enemy_count);
 
-    initialize_enemies(world, enemy_count, 4.0);
+    let kind = drifting_kind(4.0);
+    initialize_enemies(world, &mut rng, enemy_count, &kind);
 }
 
 pub fn initialize_enemies_scaled(world: &mut World) {
-    let enemy_count = get_enemy_count();
     // The percentage of the screen that should be covered in colliders
-    let area_scale = get_area_scale();
+    let (enemy_count, area_scale, mut rng) = {
+        let config = world.read_resource::<SceneConfig>();
+        (config.enemy_count, config.area_scale, config.rng())
+    };
 
     log::info!(
         "`enemies_scaled` starting with {} enemies and {} scale",
@@ -95,45 +181,128 @@ pub fn initialize_enemies_scaled(world: &mut World) {
     #[allow(clippy::cast_precision_loss)]
     let radius = ((area * area_scale) / (enemy_count as f32 * std::f32::consts::PI)).sqrt();
 
-    initialize_enemies(world, enemy_count, radius);
+    let kind = drifting_kind(radius);
+    initialize_enemies(world, &mut rng, enemy_count, &kind);
 }
 
-fn get_variable<F: std::str::FromStr>(variable: &str, default: F) -> F {
-    if let Ok(value) = std::env::var(variable) {
-        if let Ok(value) = value.parse() {
-            value
-        } else {
-            log::warn!("Invalid {}: '{}'", variable, value);
-            default
-        }
-    } else {
-        default
+/// Clones an enemy kind out of the catalogue
+///
+/// Panics if the kind doesn't exist since scenes can't do without it
+fn get_kind(world: &World, name: &str) -> EnemyKind {
+    world
+        .read_resource::<EnemyCatalogue>()
+        .get(name)
+        .unwrap_or_else(|| panic!("`{}` is missing from the enemy catalogue", name))
+        .clone()
+}
+
+/// A plain enemy of the given size that drifts in a straight line
+/// Used by the benchmarks so that only the enemy count and size changes
+///
+/// Not taken from the catalogue so that changing how enemies play
+/// doesn't change what the benchmarks measure
+fn drifting_kind(radius: f32) -> EnemyKind {
+    EnemyKind {
+        health: 10.0,
+        radius,
+        speed: 12.5 * radius,
+        color: [0.1, 0.1, 1.0, 0.5],
+        ..EnemyKind::default()
+    }
+}
+
+fn initialize_enemies(world: &mut World, rng: &mut StdRng, count: usize, kind: &EnemyKind) {
+    use rand::distributions::{Distribution, Uniform};
+    let direction = Uniform::new(-1.0, 1.0);
+    let speed = Uniform::new(f32::EPSILON, 1.0);
+    let enemy_x = Uniform::new(kind.radius, ARENA_WIDTH - kind.radius);
+    let enemy_y = Uniform::new(kind.radius, ARENA_HEIGHT - kind.radius);
+    for _ in 1..=count {
+        enemy::spawn_enemy(
+            world,
+            kind,
+            enemy_x.sample(rng),
+            enemy_y.sample(rng),
+            Vector2::new(
+                direction.sample(rng) * speed.sample(rng),
+                direction.sample(rng) * speed.sample(rng),
+            ),
+        );
+    }
+}
+
+/// A few enemies of every behavior
+pub fn initialize_behaviors(world: &mut World) {
+    let mut rng = world.read_resource::<SceneConfig>().rng();
+
+    let modes = [
+        BehaviorMode::Chase,
+        BehaviorMode::Flee,
+        BehaviorMode::Wander,
+        BehaviorMode::Orbit { radius: 25.0 },
+    ];
+    let mut kind = get_kind(world, "basic");
+    kind.radius = 3.0;
+    kind.speed = 20.0;
+    kind.acceleration = Some(40.0);
+
+    for mode in &modes {
+        kind.behavior = Some(*mode);
+        spawn_random(world, &mut rng, &kind, 3);
     }
 }
 
-fn get_enemy_count() -> usize {
-    get_variable("ENEMY_COUNT", 5000)
+/// A few enemies of every kind in the catalogue
+pub fn initialize_enemy_kinds(world: &mut World) {
+    let mut rng = world.read_resource::<SceneConfig>().rng();
+    let kinds: Vec<EnemyKind> = world
+        .read_resource::<EnemyCatalogue>()
+        .kinds
+        .values()
+        .cloned()
+        .collect();
+
+    for kind in &kinds {
+        spawn_random(world, &mut rng, kind, 3);
+    }
 }
 
-fn get_area_scale() -> f32 {
-    get_variable("AREA_SCALE", 0.4)
+/// Every kind of enemy with one of every pickup lying around
+pub fn initialize_pickups(world: &mut World) {
+    use rand::distributions::{Distribution, Uniform};
+
+    initialize_enemy_kinds(world);
+
+    let mut rng = world.read_resource::<SceneConfig>().rng();
+    let kinds: Vec<PickupKind> = world
+        .read_resource::<PickupCatalogue>()
+        .kinds
+        .values()
+        .cloned()
+        .collect();
+
+    for kind in &kinds {
+        let x = Uniform::new(kind.radius, ARENA_WIDTH - kind.radius);
+        let y = Uniform::new(kind.radius, ARENA_HEIGHT - kind.radius);
+        pickup::spawn_pickup(world, kind, x.sample(&mut rng), y.sample(&mut rng));
+    }
 }
 
-fn initialize_enemies(world: &mut World, count: usize, radius: f32) {
+/// Spawns enemies at random positions going in random directions
+fn spawn_random(world: &mut World, rng: &mut StdRng, kind: &EnemyKind, count: usize) {
     use rand::distributions::{Distribution, Uniform};
-    let mut rng = rand::thread_rng();
-    let direction = Uniform::new(-1.0, 1.0);
-    let velocity = Uniform::new(f32::EPSILON, 12.5 * radius);
-    let enemy_x = Uniform::new(radius, ARENA_WIDTH - radius);
-    let enemy_y = Uniform::new(radius, ARENA_HEIGHT - radius);
-    for _ in 1..=count {
+    let angle = Uniform::new(0.0, std::f32::consts::PI * 2.0);
+    let enemy_x = Uniform::new(kind.radius, ARENA_WIDTH - kind.radius);
+    let enemy_y = Uniform::new(kind.radius, ARENA_HEIGHT - kind.radius);
+
+    for _ in 0..count {
+        let angle = angle.sample(rng);
         enemy::spawn_enemy(
             world,
-            enemy_x.sample(&mut rng),
-            enemy_y.sample(&mut rng),
-            direction.sample(&mut rng) * velocity.sample(&mut rng),
-            direction.sample(&mut rng) * velocity.sample(&mut rng),
-            radius,
+            kind,
+            enemy_x.sample(rng),
+            enemy_y.sample(rng),
+            Vector2::new(angle.cos(), angle.sin()),
         );
     }
 }
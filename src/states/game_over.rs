@@ -0,0 +1,83 @@
+use crate::{components::Player, systems::score::Score};
+
+use amethyst::{
+    assets::Loader,
+    ecs::prelude::*,
+    prelude::*,
+    ui::{Anchor, TtfFormat, UiText, UiTransform},
+    winit::{Event, WindowEvent},
+};
+
+use log::info;
+
+/// Shown once every player has run out of health
+#[derive(Default)]
+pub struct GameOverState;
+
+impl SimpleState for GameOverState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        // Remove the players so that they can't keep on playing
+        let players: Vec<Entity> = (&world.entities(), &world.read_storage::<Player>())
+            .join()
+            .map(|(entity, _)| entity)
+            .collect();
+        world
+            .delete_entities(&players)
+            .expect("Players should still be alive");
+
+        let score = world.read_resource::<Score>();
+        info!("Game over! Final score: {}", score.total());
+        if score.player_scores.len() > 1 {
+            for (id, player_score) in score.player_scores.iter().enumerate() {
+                info!("Player {}: {}", id + 1, player_score);
+            }
+        }
+        drop(score);
+        info!("Press any key to quit");
+
+        let font = world.read_resource::<Loader>().load(
+            "fonts/FiraSans-Regular.ttf",
+            TtfFormat,
+            (),
+            &world.read_resource(),
+        );
+        let transform = UiTransform::new(
+            "game_over".to_string(),
+            Anchor::Middle,
+            Anchor::Middle,
+            0_f32,
+            0_f32,
+            0_f32,
+            100e20_f32,
+            80_f32,
+        );
+        world
+            .create_entity()
+            .with(transform)
+            .with(UiText::new(
+                font,
+                "Game Over".to_string(),
+                [1_f32; 4],
+                80_f32,
+            ))
+            .build();
+    }
+
+    fn handle_event(
+        &mut self,
+        _data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        if let StateEvent::Window(Event::WindowEvent {
+            event: WindowEvent::ReceivedCharacter(_),
+            ..
+        }) = event
+        {
+            return SimpleTrans::Quit;
+        }
+
+        SimpleTrans::None
+    }
+}
@@ -1,8 +1,10 @@
 use crate::scenes;
 use amethyst::prelude::*;
 
+mod game_over;
 mod running;
 mod scene_select;
+mod upgrade;
 
 pub fn initial_state() -> impl SimpleState {
     LoadingState
@@ -17,7 +19,7 @@ fn get_scene_cli() -> Option<running::RunningState> {
         let scene_name = &args[1];
 
         if let Some(scene) = scenes::get_scene(scene_name) {
-            Some(running::RunningState::new(scene.initializer))
+            Some(running::RunningState::new(scene))
         } else {
             panic!("`{}` is an invalid scene name!", scene_name);
         }
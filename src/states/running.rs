@@ -1,13 +1,15 @@
 use crate::{
-    components::initialize_player,
-    scenes::SceneInitializer,
+    components::{initialize_players, Health, Player},
+    scenes::{Scene, SceneConfig},
+    states::upgrade::UpgradeRng,
     sushi_cutters::{ARENA_HEIGHT, ARENA_WIDTH},
+    systems::{experience::Experience, score::Score},
 };
 
 use amethyst::{core::transform::Transform, ecs::prelude::*, prelude::*, renderer::Camera};
 
 #[cfg(feature = "benchmark")]
-use crate::util::frame_bench::FrameBench;
+use crate::util::frame_bench::{BenchMeta, BenchSettings, FrameBench};
 
 pub fn initialize_camera(world: &mut World) {
     // Setup camera in a way that our screen covers whole arena and (0, 0) is in the bottom left.
@@ -21,15 +23,29 @@ pub fn initialize_camera(world: &mut World) {
         .build();
 }
 
+/// True once every player has run out of health
+fn players_defeated(world: &World) -> bool {
+    let players = world.read_storage::<Player>();
+    let healths = world.read_storage::<Health>();
+
+    let mut any_players = false;
+    for (_, health) in (&players, &healths).join() {
+        if health.amount > 0.0 {
+            return false;
+        }
+        any_players = true;
+    }
+
+    any_players
+}
+
 pub struct RunningState {
-    initializer: Option<SceneInitializer>,
+    scene: &'static Scene,
 }
 
 impl RunningState {
-    pub fn new(initializer: SceneInitializer) -> Self {
-        Self {
-            initializer: Some(initializer),
-        }
+    pub const fn new(scene: &'static Scene) -> Self {
+        Self { scene }
     }
 }
 
@@ -37,16 +53,24 @@ impl SimpleState for RunningState {
     fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
         let world = data.world;
 
+        let config = SceneConfig::from_env();
+
         #[cfg(feature = "benchmark")]
-        world.insert(FrameBench::default());
+        world.insert(FrameBench::new(
+            BenchMeta::new(self.scene.name, "rendered", &config),
+            BenchSettings::from_env(),
+        ));
+
+        world.insert(UpgradeRng(config.system_rng("upgrades")));
+        world.insert(config);
+        world.insert(Experience::default());
+        world.insert(Score::new(self.scene.players));
 
         initialize_camera(world);
 
-        if let Some(init) = self.initializer {
-            init(world);
-        }
+        (self.scene.initializer)(world);
 
-        initialize_player(world);
+        initialize_players(world, self.scene.players);
     }
 
     fn on_stop(&mut self, _data: StateData<'_, GameData<'_, '_>>) {
@@ -58,12 +82,12 @@ impl SimpleState for RunningState {
         }
     }
 
-    fn update(&mut self, _data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
         #[cfg(feature = "benchmark")]
         {
             use amethyst::core::Time;
-            let mut bench = _data.world.write_resource::<FrameBench>();
-            let time: Time = *_data.world.read_resource::<Time>();
+            let mut bench = data.world.write_resource::<FrameBench>();
+            let time: Time = *data.world.read_resource::<Time>();
             // We could use the absolute frametime and frame number
             // but by aggregating it ourselves we know that the counter
             // only starts counting AFTER everything is initialized
@@ -74,6 +98,18 @@ impl SimpleState for RunningState {
             }
         }
 
+        // Benchmarks should always run for their full duration
+        if !cfg!(feature = "benchmark") && players_defeated(data.world) {
+            return SimpleTrans::Switch(Box::new(super::game_over::GameOverState));
+        }
+
+        // Benchmarks can't pick upgrades so they keep playing without them
+        if !cfg!(feature = "benchmark")
+            && data.world.read_resource::<Experience>().pending_upgrades > 0
+        {
+            return SimpleTrans::Push(Box::new(super::upgrade::UpgradeState::default()));
+        }
+
         SimpleTrans::None
     }
 }
@@ -29,7 +29,7 @@ impl SimpleState for SceneSelectState {
         _data: StateData<'_, GameData<'_, '_>>,
         event: StateEvent,
     ) -> SimpleTrans {
-        let s = scenes::SCENES;
+        let s = &scenes::SCENES;
 
         // TODO: Use UI instead of a keyboard input
         // Right now this is limited to only 10 scenes
@@ -39,9 +39,8 @@ impl SimpleState for SceneSelectState {
                     if let Some(num) = c.to_digit(10) {
                         let num: usize = num as usize;
                         if num < s.len() {
-                            let initializer = s[num].initializer;
                             return SimpleTrans::Switch(Box::new(
-                                super::running::RunningState::new(initializer),
+                                super::running::RunningState::new(&s[num]),
                             ));
                         } else {
                             warn!("{} is out of bounds", num);
@@ -0,0 +1,151 @@
+use crate::{
+    components::{Player, Upgrade, UpgradeTable},
+    systems::experience::Experience,
+    util::pause::Paused,
+};
+
+use amethyst::{
+    assets::Loader,
+    core::Time,
+    ecs::prelude::*,
+    prelude::*,
+    ui::{Anchor, TtfFormat, UiText, UiTransform},
+    winit::{Event, WindowEvent},
+};
+
+use log::{info, warn};
+use rand::{rngs::StdRng, seq::SliceRandom};
+
+// Number of upgrades to choose from
+const CHOICES: usize = 3;
+
+/// Random numbers for rolling upgrades, seeded from the scene like the systems
+///
+/// Kept as a resource so every level up continues the same stream
+pub struct UpgradeRng(pub StdRng);
+
+/// Pushed on top of the running state when a level is gained
+///
+/// Gameplay is paused until one of the offered upgrades is picked
+/// with a number key, the upgrade is then given to every player
+#[derive(Default)]
+pub struct UpgradeState {
+    choices: Vec<Upgrade>,
+    ui: Vec<Entity>,
+}
+
+/// Picks upgrades at random that are useful to at least one player
+fn roll_choices(world: &World) -> Vec<Upgrade> {
+    let table = world.read_resource::<UpgradeTable>();
+    let players = world.read_storage::<Player>();
+
+    let useful: Vec<&Upgrade> = table
+        .upgrades
+        .iter()
+        .filter(|upgrade| players.join().any(|player| upgrade.is_useful_for(player)))
+        .collect();
+
+    let mut rng = world.write_resource::<UpgradeRng>();
+    useful
+        .choose_multiple(&mut rng.0, CHOICES)
+        .map(|&upgrade| upgrade.clone())
+        .collect()
+}
+
+impl SimpleState for UpgradeState {
+    fn on_start(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        world.write_resource::<Paused>().0 = true;
+        world.write_resource::<Time>().set_time_scale(0.0);
+
+        self.choices = roll_choices(world);
+
+        let level = world.read_resource::<Experience>().level;
+        info!("Level {}! Choose an upgrade", level);
+        let mut lines = vec![format!("Level {}! Choose an upgrade", level)];
+        for (index, upgrade) in self.choices.iter().enumerate() {
+            info!("{}: {}", index + 1, upgrade.name);
+            lines.push(format!("{}: {}", index + 1, upgrade.name));
+        }
+
+        let font = world.read_resource::<Loader>().load(
+            "fonts/FiraSans-Regular.ttf",
+            TtfFormat,
+            (),
+            &world.read_resource(),
+        );
+        for (index, line) in lines.into_iter().enumerate() {
+            #[allow(clippy::cast_precision_loss)]
+            let y = 60_f32 - index as f32 * 40_f32;
+            let transform = UiTransform::new(
+                format!("upgrade_{}", index),
+                Anchor::Middle,
+                Anchor::Middle,
+                0_f32,
+                y,
+                0_f32,
+                100e20_f32,
+                40_f32,
+            );
+            let entity = world
+                .create_entity()
+                .with(transform)
+                .with(UiText::new(font.clone(), line, [1_f32; 4], 40_f32))
+                .build();
+            self.ui.push(entity);
+        }
+    }
+
+    fn on_stop(&mut self, data: StateData<'_, GameData<'_, '_>>) {
+        let world = data.world;
+
+        world
+            .delete_entities(&self.ui)
+            .expect("Upgrade UI should still be alive");
+        self.ui.clear();
+
+        world.write_resource::<Time>().set_time_scale(1.0);
+        world.write_resource::<Paused>().0 = false;
+    }
+
+    fn handle_event(
+        &mut self,
+        data: StateData<'_, GameData<'_, '_>>,
+        event: StateEvent,
+    ) -> SimpleTrans {
+        if let StateEvent::Window(Event::WindowEvent {
+            event: WindowEvent::ReceivedCharacter(c),
+            ..
+        }) = event
+        {
+            if let Some(num) = c.to_digit(10) {
+                // Choices are numbered from 1 on screen
+                let upgrade = (num as usize)
+                    .checked_sub(1)
+                    .and_then(|index| self.choices.get(index));
+                if let Some(upgrade) = upgrade {
+                    info!("Picked {}", upgrade.name);
+                    for player in (&mut data.world.write_storage::<Player>()).join() {
+                        upgrade.apply(player);
+                    }
+                    data.world.write_resource::<Experience>().pending_upgrades -= 1;
+                    return SimpleTrans::Pop;
+                }
+                warn!("{} isn't one of the choices", num);
+            }
+        }
+
+        SimpleTrans::None
+    }
+
+    fn update(&mut self, data: &mut StateData<'_, GameData<'_, '_>>) -> SimpleTrans {
+        // Every upgrade has been taken so there's nothing to choose from
+        if self.choices.is_empty() {
+            data.world.write_resource::<Experience>().pending_upgrades -= 1;
+            return SimpleTrans::Pop;
+        }
+
+        SimpleTrans::None
+    }
+}
@@ -0,0 +1,27 @@
+use amethyst::{
+    core::Time,
+    ecs::prelude::{Read, System, Write},
+    input::InputHandler,
+};
+
+use crate::input::{actions::ActionStates, bindings::InputBindingTypes};
+
+/// Updates `ActionStates` from the `InputHandler` so that other systems
+/// can tell when actions are pressed and released
+pub struct ActionStateSystem;
+
+impl<'s> System<'s> for ActionStateSystem {
+    type SystemData = (
+        Read<'s, InputHandler<InputBindingTypes>>,
+        Read<'s, Time>,
+        Write<'s, ActionStates>,
+    );
+
+    fn run(&mut self, (input, time, mut states): Self::SystemData) {
+        let delta = f64::from(time.delta_seconds());
+        for action in input.bindings.actions() {
+            let down = input.action_is_down(action).unwrap_or(false);
+            states.update(action, down, delta);
+        }
+    }
+}
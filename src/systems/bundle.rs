@@ -0,0 +1,139 @@
+use amethyst::{
+    core::{bundle::SystemBundle, transform::TransformBundle},
+    ecs::prelude::{DispatcherBuilder, World},
+    error::Error,
+};
+
+use crate::util::{pause::pausable, timed::timed};
+
+/// Adds every system that simulates the game (movement, collisions, damage)
+///
+/// These systems don't need a window so the same bundle is used for
+/// the game and for headless benchmarks. Systems that draw or touch the UI
+/// are added separately in `main.rs`
+#[derive(Default)]
+pub struct GameplayBundle<'a> {
+    dep: &'a [&'a str],
+}
+
+impl<'a> GameplayBundle<'a> {
+    /// Systems that need to run before reading actions (like input)
+    pub const fn with_dep(mut self, dep: &'a [&'a str]) -> Self {
+        self.dep = dep;
+        self
+    }
+}
+
+impl<'a, 'b, 'c> SystemBundle<'a, 'b> for GameplayBundle<'c> {
+    fn build(
+        self,
+        world: &mut World,
+        builder: &mut DispatcherBuilder<'a, 'b>,
+    ) -> Result<(), Error> {
+        // Gameplay systems are wrapped with `timed` so that they show up
+        // in benchmarks. This does nothing without the benchmark feature.
+        // They are also `pausable` so that nothing moves while choosing an upgrade
+        builder.add(
+            pausable(timed("action_state_system", super::ActionStateSystem)),
+            "action_state_system",
+            self.dep,
+        );
+        builder.add(
+            pausable(timed("player_control", super::PlayerControlSystem)),
+            "player_control",
+            &["action_state_system"],
+        );
+        builder.add(
+            pausable(timed("steering_system", super::SteeringSystem::default())),
+            "steering_system",
+            &[],
+        );
+        builder.add(
+            pausable(timed("velocity_system", super::VelocitySystem)),
+            "velocity_system",
+            &["steering_system"],
+        );
+
+        TransformBundle::new()
+            .with_dep(&["player_control", "velocity_system"])
+            .build(world, builder)?;
+
+        builder.add(
+            pausable(timed(
+                "collisions_system",
+                super::CollisionsSystem::default(),
+            )),
+            "collisions_system",
+            &["transform_system"],
+        );
+        builder.add(
+            pausable(timed("border_system", super::BorderSystem)),
+            "border_system",
+            &["transform_system"],
+        );
+        builder.add(
+            pausable(timed("damage_system", super::DamageSystem)),
+            "damage_system",
+            &["collisions_system"],
+        );
+        // After damage so that attacks that used up their hit list are killed the same frame
+        builder.add(
+            pausable(timed("kill_after_system", super::KillAfterSystem)),
+            "kill_after_system",
+            &["collisions_system", "damage_system"],
+        );
+        builder.add(
+            pausable(timed(
+                "status_effect_system",
+                super::StatusEffectSystem::default(),
+            )),
+            "status_effect_system",
+            &["damage_system"],
+        );
+        builder.add(
+            pausable(timed("pickup_system", super::PickupSystem)),
+            "pickup_system",
+            &["collisions_system"],
+        );
+        builder.add(
+            pausable(timed("drop_system", super::DropSystem::default())),
+            "drop_system",
+            &["damage_system", "status_effect_system"],
+        );
+        builder.add(
+            pausable(timed("split_system", super::SplitSystem::default())),
+            "split_system",
+            &["damage_system", "status_effect_system"],
+        );
+        builder.add(
+            pausable(timed(
+                "experience_system",
+                super::ExperienceSystem::default(),
+            )),
+            "experience_system",
+            &["damage_system", "status_effect_system"],
+        );
+        builder.add(
+            pausable(timed("score_system", super::ScoreSystem::default())),
+            "score_system",
+            &["damage_system", "status_effect_system"],
+        );
+        // Anything that reacts to deaths should run before the reaper
+        builder.add(
+            pausable(timed("reaper_system", super::ReaperSystem::default())),
+            "reaper_system",
+            &[
+                "damage_system",
+                "kill_after_system",
+                "status_effect_system",
+                "pickup_system",
+                "drop_system",
+                "split_system",
+                "experience_system",
+                "score_system",
+            ],
+        );
+
+        Ok(())
+    }
+}
@@ -11,7 +11,7 @@ use amethyst::{
 
 use crate::input::bindings::{ActionBinding, InputBindingTypes};
 
-use crate::components::{BoxCollider, CircleCollider, Collisions, Enemy};
+use crate::components::{BoxCollider, CircleCollider, Collisions, Enemy, Pickup};
 use crate::util::transform::global_translation;
 
 pub struct CollisionDebugState {
@@ -38,6 +38,7 @@ impl<'s> System<'s> for CollisionDebugSystem {
         ReadStorage<'s, BoxCollider>,
         ReadStorage<'s, CircleCollider>,
         ReadStorage<'s, Enemy>,
+        ReadStorage<'s, Pickup>,
         ReadStorage<'s, Transform>,
         ReadStorage<'s, Collisions>,
         Write<'s, DebugLines>,
@@ -47,7 +48,17 @@ impl<'s> System<'s> for CollisionDebugSystem {
 
     fn run(
         &mut self,
-        (boxes, circles, enemies, transforms, collisions, mut debug, mut state, input): Self::SystemData,
+        (
+            boxes,
+            circles,
+            enemies,
+            pickups,
+            transforms,
+            collisions,
+            mut debug,
+            mut state,
+            input,
+        ): Self::SystemData,
     ) {
         let button_down = input
             .action_is_down(&ActionBinding::ToggleColliders)
@@ -69,16 +80,23 @@ impl<'s> System<'s> for CollisionDebugSystem {
 
         let red = Srgba::new(0.7, 0.2, 0.2, 1.0);
         let green = Srgba::new(0.2, 0.7, 0.2, 1.0);
-        let blue = Srgba::new(0.1, 0.1, 1.0, 0.5);
-        for (circle, transform, collision, enemy) in
-            (&circles, &transforms, collisions.maybe(), enemies.maybe()).join()
+        for (circle, transform, collision, enemy, pickup) in (
+            &circles,
+            &transforms,
+            collisions.maybe(),
+            enemies.maybe(),
+            pickups.maybe(),
+        )
+            .join()
         {
             let circle_point = Point3::from(global_translation(transform));
 
             let color = if collision.is_some() {
                 red
-            } else if enemy.is_some() {
-                blue
+            } else if let Some(enemy) = enemy {
+                enemy.color
+            } else if let Some(pickup) = pickup {
+                pickup.color
             } else {
                 green
             };
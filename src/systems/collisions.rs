@@ -11,12 +11,12 @@ use amethyst::{
 use crate::components::{BoxCollider, CircleCollider, CollisionData, Collisions};
 use crate::util::transform::global_translation;
 
-#[cfg(feature = "benchmark")]
-use crate::util::frame_bench::FrameBench;
-
 #[derive(Default)]
 pub struct CollisionsSystem {
     collision_pool: Vec<Collisions>,
+    // Entities that had collisions last frame
+    // Kept around so that its memory can be reused
+    stale: Vec<Entity>,
     allocator: bumpalo::Bump,
 }
 
@@ -27,9 +27,6 @@ pub struct CollisionsSystemData<'a> {
     circles: ReadStorage<'a, CircleCollider>,
     transforms: ReadStorage<'a, Transform>,
     collisions: WriteStorage<'a, Collisions>,
-
-    #[cfg(feature = "benchmark")]
-    bench: Write<'a, FrameBench>,
 }
 
 impl<'s> System<'s> for CollisionsSystem {
@@ -43,19 +40,22 @@ impl<'s> System<'s> for CollisionsSystem {
             circles,
             transforms,
             mut collisions,
-
-            #[cfg(feature = "benchmark")]
-            mut bench,
         }: Self::SystemData,
     ) {
-        // We want this to last the whole scope so we must store it as a variable
-        #[cfg(feature = "benchmark")]
-        let _scope = bench.time_scope("Collisions".to_string());
-
         // Clear all collisions from the previous frame
         // And add them into the collision pool
-        for x in collisions.drain().join() {
-            self.collision_pool.push(x);
+        //
+        // This doesn't use `collisions.drain()` because that clones
+        // the storage mask which allocates every frame
+        self.stale.extend(
+            (&entities, collisions.mask())
+                .join()
+                .map(|(entity, _)| entity),
+        );
+        for entity in self.stale.drain(..) {
+            if let Some(x) = collisions.remove(entity) {
+                self.collision_pool.push(x);
+            }
         }
 
         // Create a new cache on the top of the prereserved memory
@@ -181,3 +181,51 @@ fn add_collision(
         collisions.insert(target, c).unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::alloc_counter::thread_stats;
+
+    #[test]
+    fn steady_state_does_not_allocate() {
+        let mut world = World::new();
+        let mut system = CollisionsSystem::default();
+        System::setup(&mut system, &mut world);
+
+        // Everything is at the origin so every collider collides with every other collider
+        for _ in 0..10 {
+            world
+                .create_entity()
+                .with(CircleCollider { radius: 1.0 })
+                .with(Transform::default())
+                .build();
+            world
+                .create_entity()
+                .with(BoxCollider {
+                    width: 1.0,
+                    height: 1.0,
+                })
+                .with(Transform::default())
+                .build();
+        }
+        world.maintain();
+
+        // The first frames fill up the collision pool and the bump allocator.
+        // Pooled components end up on different entities every frame and each
+        // of them grows on its own, so it takes a few frames for all of them to settle
+        for _ in 0..10 {
+            system.run_now(&world);
+            world.maintain();
+        }
+
+        let before = thread_stats();
+        for _ in 0..10 {
+            system.run_now(&world);
+            world.maintain();
+        }
+        let allocations = thread_stats() - before;
+
+        assert_eq!(allocations.allocations, 0);
+    }
+}
@@ -1,38 +1,173 @@
+#![allow(clippy::type_repetition_in_bounds)]
+
+use amethyst::core::{math::Vector3, Time, Transform};
 use amethyst::ecs::prelude::*;
+use amethyst::shrev::EventChannel;
 use log::debug;
 
-use crate::components::{Collisions, Damage, Health};
+use crate::components::{
+    Collisions, Damage, Faction, Health, HitList, InflictsStatus, Invulnerability, Player,
+    Resistances, StatusEffects, Velocity,
+};
+use crate::events::{DeathCause, DeathEvent};
+use crate::util::transform::global_translation;
 
 pub struct DamageSystem;
 
+#[derive(SystemData)]
+pub struct DamageSystemData<'a> {
+    entities: Entities<'a>,
+    collisions: ReadStorage<'a, Collisions>,
+    damages: ReadStorage<'a, Damage>,
+    hit_lists: WriteStorage<'a, HitList>,
+    healths: WriteStorage<'a, Health>,
+    resistances: ReadStorage<'a, Resistances>,
+    factions: ReadStorage<'a, Faction>,
+    invulnerabilities: WriteStorage<'a, Invulnerability>,
+    velocities: WriteStorage<'a, Velocity>,
+    inflicts: ReadStorage<'a, InflictsStatus>,
+    effects: StatusEffects<'a>,
+    players: ReadStorage<'a, Player>,
+    transforms: ReadStorage<'a, Transform>,
+    time: Read<'a, Time>,
+    deaths: Write<'a, EventChannel<DeathEvent>>,
+}
+
 impl<'s> System<'s> for DamageSystem {
-    #[allow(clippy::type_complexity)]
-    type SystemData = (
-        Entities<'s>,
-        ReadStorage<'s, Collisions>,
-        ReadStorage<'s, Damage>,
-        WriteStorage<'s, Health>,
-    );
+    type SystemData = DamageSystemData<'s>;
 
     /// Applies all damages from entities with damage to entities with health
     ///
-    /// If an entities health reaches below zero then remove it from the game
+    /// Sources with a `HitList` only damage each target once
+    /// The damage is reduced by the target's resistances and shield and knocks
+    /// the target away from the source if it can move
+    /// Stunned sources don't deal damage and `InflictsStatus` effects are
+    /// given to everything that is hit
+    ///
+    /// If an entities health reaches below zero then publish a `DeathEvent` for it
+    /// Players are never removed, running out of health ends the game instead
     /// This can reasonably be split into two separate systems if there are other sources
     /// Of damage
-    fn run(&mut self, (entities, collisions, damages, mut healths): Self::SystemData) {
+    fn run(
+        &mut self,
+        DamageSystemData {
+            entities,
+            collisions,
+            damages,
+            mut hit_lists,
+            mut healths,
+            resistances,
+            factions,
+            mut invulnerabilities,
+            mut velocities,
+            inflicts,
+            mut effects,
+            players,
+            transforms,
+            time,
+            mut deaths,
+        }: Self::SystemData,
+    ) {
+        let now = time.absolute_time_seconds();
+        let position = |entity| {
+            transforms
+                .get(entity)
+                .map_or_else(Vector3::zeros, global_translation)
+        };
+
         // For each collider with a damage
-        for (collision_entries, damage) in (&collisions, &damages).join() {
+        for (source, collision_entries, damage, source_faction, mut hit_list) in (
+            &entities,
+            &collisions,
+            &damages,
+            factions.maybe(),
+            (&mut hit_lists).maybe(),
+        )
+            .join()
+        {
+            if effects.stuns.contains(source) {
+                continue;
+            }
+
             // For each collision with a health
-            for (entity, _, health) in (&entities, collision_entries.mask(), &mut healths).join() {
-                health.amount -= damage.amount;
+            for (entity, _, health, faction, invulnerability) in (
+                &entities,
+                collision_entries.mask(),
+                &mut healths,
+                factions.maybe(),
+                (&mut invulnerabilities).maybe(),
+            )
+                .join()
+            {
+                // Nothing can hurt its own side
+                if source_faction.is_some() && source_faction == faction {
+                    continue;
+                }
+
+                // Already died this frame
+                if health.amount <= 0.0 {
+                    continue;
+                }
+
+                if let Some(hit_list) = &hit_list {
+                    if hit_list.is_exhausted() || hit_list.has_hit(entity) {
+                        continue;
+                    }
+                }
+
+                if let Some(invulnerability) = invulnerability {
+                    if invulnerability.until > now {
+                        continue;
+                    }
+                    invulnerability.until = now + invulnerability.duration;
+                }
+
+                let mut amount = resistances
+                    .get(entity)
+                    .map_or(damage.amount, |resistances| {
+                        resistances.apply(damage.amount, damage.kind)
+                    });
+                if let Some(shield) = effects.shields.get_mut(entity) {
+                    amount = shield.absorb(amount);
+                }
+
+                health.amount -= amount;
                 debug!(
-                    "{:?} took {} damage ({} health left)",
-                    entity, damage.amount, health.amount
+                    "{:?} took {} {:?} damage ({} health left)",
+                    entity, amount, damage.kind, health.amount
                 );
+
+                if let Some(hit_list) = &mut hit_list {
+                    hit_list.add(entity);
+                }
+                if let Some(inflicts) = inflicts.get(source) {
+                    for &effect in &inflicts.effects {
+                        effect.apply(entity, now, &mut effects);
+                    }
+                }
+
+                if damage.knockback != 0.0 {
+                    if let Some(velocity) = velocities.get_mut(entity) {
+                        let away = (position(entity) - position(source))
+                            .try_normalize(f32::EPSILON)
+                            .unwrap_or_else(Vector3::zeros);
+                        velocity.knockback += away * damage.knockback;
+                    }
+                }
+
                 // If the health of the target is less than 0 then delet this
                 if health.amount <= 0.0 {
-                    debug!("{:?} kicked the bucket", entity);
-                    entities.delete(entity).expect("Something wrong happened");
+                    if players.contains(entity) {
+                        debug!("{:?} ran out of health", entity);
+                    } else {
+                        debug!("{:?} kicked the bucket", entity);
+                        deaths.single_write(DeathEvent {
+                            entity,
+                            cause: DeathCause::Damage,
+                            position: position(entity),
+                            killer: Some(source),
+                        });
+                    }
                 }
             }
         }
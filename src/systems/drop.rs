@@ -0,0 +1,77 @@
+use amethyst::{
+    core::Time,
+    ecs::{prelude::*, LazyUpdate},
+    shrev::{EventChannel, ReaderId},
+};
+use log::debug;
+use rand::{rngs::StdRng, Rng};
+
+use crate::components::{build_pickup, Enemy, PickupCatalogue};
+use crate::events::{DeathCause, DeathEvent};
+use crate::scenes::SceneConfig;
+
+/// Rolls the drop table in `PickupCatalogue` when an enemy is killed
+/// and spawns the pickup where it died
+#[derive(Default)]
+pub struct DropSystem {
+    reader: Option<ReaderId<DeathEvent>>,
+    // Seeded from the scene so that runs with the same seed drop the same pickups
+    rng: Option<StdRng>,
+}
+
+impl<'s> System<'s> for DropSystem {
+    type SystemData = (
+        Entities<'s>,
+        Read<'s, EventChannel<DeathEvent>>,
+        ReadStorage<'s, Enemy>,
+        Read<'s, PickupCatalogue>,
+        Read<'s, Time>,
+        Read<'s, LazyUpdate>,
+        Option<Read<'s, SceneConfig>>,
+    );
+
+    fn setup(&mut self, world: &mut World) {
+        Self::SystemData::setup(world);
+        self.reader = Some(
+            world
+                .fetch_mut::<EventChannel<DeathEvent>>()
+                .register_reader(),
+        );
+    }
+
+    fn run(
+        &mut self,
+        (entities, deaths, enemies, catalogue, time, lazy, config): Self::SystemData,
+    ) {
+        let rng = match &config {
+            Some(config) => self
+                .rng
+                .get_or_insert_with(|| config.system_rng("drop_system")),
+            // No scene has started so nothing can have died
+            None => return,
+        };
+
+        for death in deaths.read(self.reader.as_mut().expect("setup wasn't called")) {
+            // Only enemies that were killed drop anything
+            if death.cause != DeathCause::Damage || !enemies.contains(death.entity) {
+                continue;
+            }
+
+            let kind = catalogue
+                .drops
+                .iter()
+                .find(|drop| rng.gen::<f32>() < drop.chance)
+                .and_then(|drop| catalogue.get(&drop.pickup));
+            if let Some(kind) = kind {
+                debug!("{:?} dropped a pickup", death.entity);
+                build_pickup(
+                    lazy.create_entity(&entities),
+                    kind,
+                    death.position,
+                    time.absolute_time_seconds(),
+                )
+                .build();
+            }
+        }
+    }
+}
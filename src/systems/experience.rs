@@ -0,0 +1,66 @@
+use amethyst::{
+    ecs::prelude::*,
+    shrev::{EventChannel, ReaderId},
+};
+use log::info;
+
+use crate::components::{Enemy, UpgradeTable};
+use crate::events::{DeathCause, DeathEvent};
+
+/// Experience shared by every player
+#[derive(Default)]
+pub struct Experience {
+    /// Experience towards the next level
+    pub xp: u32,
+    pub level: u32,
+    /// Levels gained that haven't been spent on an upgrade yet
+    pub pending_upgrades: u32,
+}
+
+/// Gives experience for every enemy killed and levels up once there's enough
+#[derive(Default)]
+pub struct ExperienceSystem {
+    reader: Option<ReaderId<DeathEvent>>,
+}
+
+impl<'s> System<'s> for ExperienceSystem {
+    type SystemData = (
+        Read<'s, EventChannel<DeathEvent>>,
+        ReadStorage<'s, Enemy>,
+        Read<'s, UpgradeTable>,
+        Write<'s, Experience>,
+    );
+
+    fn setup(&mut self, world: &mut World) {
+        Self::SystemData::setup(world);
+        self.reader = Some(
+            world
+                .fetch_mut::<EventChannel<DeathEvent>>()
+                .register_reader(),
+        );
+    }
+
+    fn run(&mut self, (deaths, enemies, table, mut experience): Self::SystemData) {
+        for death in deaths.read(self.reader.as_mut().expect("setup wasn't called")) {
+            // Enemies that time out or crash into something weren't earned
+            if death.cause != DeathCause::Damage {
+                continue;
+            }
+            if let Some(enemy) = enemies.get(death.entity) {
+                experience.xp += enemy.xp;
+            }
+        }
+
+        // A single big kill can be worth more than one level
+        loop {
+            let needed = table.levels.xp_to_next(experience.level);
+            if experience.xp < needed {
+                break;
+            }
+            experience.xp -= needed;
+            experience.level += 1;
+            experience.pending_upgrades += 1;
+            info!("Reached level {}", experience.level);
+        }
+    }
+}
@@ -1,37 +1,94 @@
-use amethyst::core::Time;
+use amethyst::core::{math::Vector3, Time, Transform};
 use amethyst::ecs::prelude::*;
+use amethyst::shrev::EventChannel;
 
 use crate::components::{
     markers::{KillAfterCollision, KillAfterTime},
-    Collisions,
+    Collisions, HitList,
 };
+use crate::events::{DeathCause, DeathEvent};
+use crate::util::transform::global_translation;
 
 pub struct KillAfterSystem;
 
 // This system handles kill after collide and kill after time
 // should be separated into two separate systems
 impl<'s> System<'s> for KillAfterSystem {
+    #[allow(clippy::type_complexity)]
     type SystemData = (
         Entities<'s>,
         WriteStorage<'s, KillAfterCollision>,
         WriteStorage<'s, KillAfterTime>,
         ReadStorage<'s, Collisions>,
+        ReadStorage<'s, HitList>,
+        ReadStorage<'s, Transform>,
         Read<'s, Time>,
+        Write<'s, EventChannel<DeathEvent>>,
     );
 
-    fn run(&mut self, (entities, ka_col, ka_time, collisions, time): Self::SystemData) {
+    fn run(
+        &mut self,
+        (
+            entities,
+            ka_col,
+            ka_time,
+            collisions,
+            hit_lists,
+            transforms,
+            time,
+            mut deaths,
+        ): Self::SystemData,
+    ) {
+        let position = |entity| {
+            transforms
+                .get(entity)
+                .map_or_else(Vector3::zeros, global_translation)
+        };
+
         // Kill all entities that have been collided with and have
         // the kill after collision marker
-        for (entity, _, _) in (&entities, ka_col.mask(), collisions.mask()).join() {
-            // Should Handle unwrap some day
-            entities.delete(entity).unwrap();
+        for (entity, _) in (&entities, ka_col.mask()).join() {
+            if collided(entity, &collisions, &hit_lists) {
+                deaths.single_write(DeathEvent {
+                    entity,
+                    cause: DeathCause::Collision,
+                    position: position(entity),
+                    killer: None,
+                });
+            }
         }
 
         // Kill all entities that have lived their lifetime
         for (entity, timing) in (&entities, &ka_time).join() {
+            // Already killed by a collision
+            if ka_col.contains(entity) && collided(entity, &collisions, &hit_lists) {
+                continue;
+            }
+
             if timing.time <= time.absolute_time_seconds() {
-                entities.delete(entity).unwrap();
+                deaths.single_write(DeathEvent {
+                    entity,
+                    cause: DeathCause::Timeout,
+                    position: position(entity),
+                    killer: None,
+                });
             }
         }
     }
 }
+
+/// Whether an entity with `KillAfterCollision` dies this frame
+///
+/// Entities with a `HitList` pierce through what they collide with
+/// until they have hit as many targets as they can
+fn collided(
+    entity: Entity,
+    collisions: &ReadStorage<Collisions>,
+    hit_lists: &ReadStorage<HitList>,
+) -> bool {
+    collisions.contains(entity)
+        && match hit_lists.get(entity) {
+            Some(hit_list) => hit_list.is_exhausted(),
+            None => true,
+        }
+}
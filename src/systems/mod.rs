@@ -1,17 +1,35 @@
+pub use self::action_states::ActionStateSystem;
 pub use self::border_collision::BorderSystem;
+pub use self::bundle::GameplayBundle;
 pub use self::collision_debug::CollisionDebugSystem;
 pub use self::collisions::CollisionsSystem;
 pub use self::damage::DamageSystem;
+pub use self::drop::DropSystem;
+pub use self::experience::ExperienceSystem;
 pub use self::kill_after::KillAfterSystem;
+pub use self::pickup::PickupSystem;
 pub use self::player_control::PlayerControlSystem;
-pub use self::score::ScoreSystemDesc;
+pub use self::reaper::ReaperSystem;
+pub use self::score::{ScoreSystem, ScoreTextSystemDesc};
+pub use self::split::SplitSystem;
+pub use self::status_effects::StatusEffectSystem;
+pub use self::steering::SteeringSystem;
 pub use self::velocity::VelocitySystem;
 
+pub mod action_states;
 pub mod border_collision;
+pub mod bundle;
 pub mod collision_debug;
 pub mod collisions;
 pub mod damage;
+pub mod drop;
+pub mod experience;
 pub mod kill_after;
+pub mod pickup;
 pub mod player_control;
+pub mod reaper;
 pub mod score;
+pub mod split;
+pub mod status_effects;
+pub mod steering;
 pub mod velocity;
@@ -0,0 +1,120 @@
+#![allow(clippy::type_repetition_in_bounds)]
+
+use amethyst::core::{math::Vector3, Time, Transform};
+use amethyst::ecs::prelude::*;
+use amethyst::shrev::EventChannel;
+use log::debug;
+
+use crate::components::{
+    Buff, Buffs, Collisions, Health, KillAfterTime, Pickup, PickupEffect, Player, StatusEffects,
+};
+use crate::events::{DeathCause, DeathEvent};
+use crate::util::transform::global_translation;
+
+/// Gives the effects of pickups to the players that touch them
+///
+/// Collected pickups are removed with a `DeathEvent` with the player as the killer
+pub struct PickupSystem;
+
+#[derive(SystemData)]
+pub struct PickupSystemData<'a> {
+    entities: Entities<'a>,
+    collisions: ReadStorage<'a, Collisions>,
+    pickups: ReadStorage<'a, Pickup>,
+    players: ReadStorage<'a, Player>,
+    lifetimes: ReadStorage<'a, KillAfterTime>,
+    healths: WriteStorage<'a, Health>,
+    buffs: WriteStorage<'a, Buffs>,
+    effects: StatusEffects<'a>,
+    transforms: ReadStorage<'a, Transform>,
+    time: Read<'a, Time>,
+    deaths: Write<'a, EventChannel<DeathEvent>>,
+}
+
+impl<'s> System<'s> for PickupSystem {
+    type SystemData = PickupSystemData<'s>;
+
+    fn run(
+        &mut self,
+        PickupSystemData {
+            entities,
+            collisions,
+            pickups,
+            players,
+            lifetimes,
+            mut healths,
+            mut buffs,
+            mut effects,
+            transforms,
+            time,
+            mut deaths,
+        }: Self::SystemData,
+    ) {
+        let now = time.absolute_time_seconds();
+
+        for (entity, pickup, collision_entries) in (&entities, &pickups, &collisions).join() {
+            // Already timed out, the kill after system takes care of it
+            if matches!(lifetimes.get(entity), Some(lifetime) if lifetime.time <= now) {
+                continue;
+            }
+
+            // Only one player gets it and players that ran out of health can't take it
+            let collector = (&entities, collision_entries.mask(), &players)
+                .join()
+                .map(|(player, _, _)| player)
+                .find(
+                    |&player| !matches!(healths.get(player), Some(health) if health.amount <= 0.0),
+                );
+            let collector = if let Some(collector) = collector {
+                collector
+            } else {
+                continue;
+            };
+
+            for &effect in &pickup.effects {
+                match effect {
+                    PickupEffect::Heal { amount } => {
+                        if let Some(health) = healths.get_mut(collector) {
+                            health.amount = (health.amount + amount).min(health.max);
+                        }
+                    }
+                    PickupEffect::AttackSpeed {
+                        multiplier,
+                        duration,
+                    } => {
+                        if let Some(buffs) = buffs.get_mut(collector) {
+                            buffs.attack_speed = Buff::new(multiplier, now + duration);
+                        }
+                    }
+                    PickupEffect::HitboxSize {
+                        multiplier,
+                        duration,
+                    } => {
+                        if let Some(buffs) = buffs.get_mut(collector) {
+                            buffs.hitbox_size = Buff::new(multiplier, now + duration);
+                        }
+                    }
+                    PickupEffect::ScoreMultiplier {
+                        multiplier,
+                        duration,
+                    } => {
+                        if let Some(buffs) = buffs.get_mut(collector) {
+                            buffs.score = Buff::new(multiplier, now + duration);
+                        }
+                    }
+                    PickupEffect::Status(status) => status.apply(collector, now, &mut effects),
+                }
+            }
+
+            debug!("{:?} picked up {:?}", collector, entity);
+            deaths.single_write(DeathEvent {
+                entity,
+                cause: DeathCause::Collision,
+                position: transforms
+                    .get(entity)
+                    .map_or_else(Vector3::zeros, global_translation),
+                killer: Some(collector),
+            });
+        }
+    }
+}
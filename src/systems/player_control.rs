@@ -1,10 +1,20 @@
-use crate::components::{BoxCollider, Damage, KillAfterCollision, KillAfterTime, Player};
-use crate::input::bindings::{ActionBinding, AxisBinding, InputBindingTypes};
+use crate::components::{
+    build_attack, build_projectile, Buffs, DashConfig, Health, Invulnerability, Owner, Player,
+    PlayerConfig, Slow, Stun,
+};
+use crate::input::{
+    actions::ActionStates,
+    bindings::{ActionBinding, AxisBinding, InputBindingTypes},
+};
 use amethyst::{
-    core::{math::Vector3, Parent, Time, Transform},
+    core::{
+        math::{Vector2, Vector3},
+        Parent, Time, Transform,
+    },
     ecs::{prelude::*, LazyUpdate},
     input::InputHandler,
 };
+use log::debug;
 
 pub struct PlayerControlSystem;
 
@@ -13,22 +23,22 @@ type PlayerControlSystemData<'s> = (
     Entities<'s>,
     WriteStorage<'s, Transform>,
     WriteStorage<'s, Player>,
+    WriteStorage<'s, Invulnerability>,
+    ReadStorage<'s, Health>,
+    ReadStorage<'s, Buffs>,
+    ReadStorage<'s, Slow>,
+    ReadStorage<'s, Stun>,
+    ReadExpect<'s, PlayerConfig>,
     Read<'s, InputHandler<InputBindingTypes>>,
+    Read<'s, ActionStates>,
     Read<'s, Time>,
     Read<'s, LazyUpdate>,
 );
 
-// These constants should be attached to either the player or a config file
-const ATTACK_DAMAGE: f32 = 1.0;
-const COLLIDER_TIMEOUT: f64 = 0.1;
-const TIME_TO_ATTACK: f64 = 0.4;
-const ATTACK_BOX_SIZE: [f32; 2] = [10.0, 10.0];
-
 impl<'s> System<'s> for PlayerControlSystem {
     type SystemData = PlayerControlSystemData<'s>;
 
-    // There should only really be one player but if we want the
-    // player to control multiple things this could be a way to do it
+    // Every player reads the bindings for their own `PlayerId`
     //
     // This is also a simplistic view of how movement should be handled
     // Subject to change in the future
@@ -43,22 +53,81 @@ impl<'s> System<'s> for PlayerControlSystem {
             // Player movement and input
             mut transforms,
             mut players,
+            mut invulnerabilities,
+            healths,
+            buffs,
+            slows,
+            stuns,
+            config,
             input,
+            actions,
             time,
             lazy,
         ): Self::SystemData,
     ) {
-        for (entity, transform, player) in (&entities, &mut transforms, &mut players).join() {
+        // Stunned players can't move or attack
+        for (entity, transform, player, invulnerability, health, buffs, slow, _) in (
+            &entities,
+            &mut transforms,
+            &mut players,
+            (&mut invulnerabilities).maybe(),
+            healths.maybe(),
+            buffs.maybe(),
+            slows.maybe(),
+            !&stuns,
+        )
+            .join()
+        {
+            // Players that ran out of health sit out until everyone else does
+            if matches!(health, Some(health) if health.amount <= 0.0) {
+                continue;
+            }
+
             // Custom bindings might be better for the future but right now
             // this is good enough
             // https://book.amethyst.rs/stable/input/how_to_define_custom_control_bindings.html
-            let x_movement = input.axis_value(&AxisBinding::Horizontal).unwrap_or(0.);
-            let y_movement = input.axis_value(&AxisBinding::Vertical).unwrap_or(0.);
+            let x_movement = input
+                .axis_value(&AxisBinding::Horizontal(player.id))
+                .unwrap_or(0.);
+            let y_movement = input
+                .axis_value(&AxisBinding::Vertical(player.id))
+                .unwrap_or(0.);
+            let speed_multiplier = slow.map_or(1.0, |slow| slow.multiplier);
+            let now = time.absolute_time_seconds();
+            let attack_speed = buffs.map_or(1.0, |buffs| buffs.attack_speed.get(now));
+            let hitbox_size = buffs.map_or(1.0, |buffs| buffs.hitbox_size.get(now));
 
-            // Normalizing a vector of length 0 will result in a panic
-            // Not very rusty but we have to check to make sure the movement isn't
-            // (0.0, 0.0)
-            if x_movement != 0.0 || y_movement != 0.0 {
+            let frame = Frame {
+                entities: &entities,
+                lazy: &lazy,
+                actions: &actions,
+                now,
+            };
+
+            if let Some(dash) = &config.dash {
+                start_dash(
+                    &frame,
+                    player,
+                    invulnerability,
+                    dash,
+                    x_movement,
+                    y_movement,
+                );
+            }
+
+            // Dashing takes over from walking until it ends
+            // There are no solid walls yet, once there are they should
+            // stop a dash the same way they stop walking
+            let dashing = config.dash.as_ref().filter(|_| now < player.dash_until);
+            if let Some(dash) = dashing {
+                let movement =
+                    player.dash_direction * dash.speed * speed_multiplier * time.delta_seconds();
+                transform.prepend_translation(Vector3::new(movement.x, movement.y, 0.0));
+            } else if x_movement != 0.0 || y_movement != 0.0 {
+                // Normalizing a vector of length 0 will result in a panic
+                // Not very rusty but we have to check to make sure the movement isn't
+                // (0.0, 0.0)
+                //
                 // Normalize so that diagonals aren't faster than cardinals
                 // Then multiply by speed and finally by the timestep
                 // To make sure the game isn't tied to the framerate
@@ -66,45 +135,178 @@ impl<'s> System<'s> for PlayerControlSystem {
                 // Maybe should refactor to use try_normalize instead
                 let movement = Vector3::from([x_movement, y_movement, 0.]).normalize()
                     * player.speed
+                    * speed_multiplier
                     // When this is moved over to fixed dispatch
                     // This should be time.fixed_seconds
                     * time.delta_seconds();
                 transform.prepend_translation(movement);
             }
 
-            // This has to be two nested ifs because of the way that let matching works
-            if let Some(attack) = input.action_is_down(&ActionBinding::Attack) {
-                // If the button is down and the time to next attack is less than
-                // what is in the struct
-                if attack && player.next_attack <= time.absolute_time_seconds() {
-                    player.next_attack = time.absolute_time_seconds() + TIME_TO_ATTACK;
-
-                    let transform =
-                        Transform::from(Vector3::from([0.0, ATTACK_BOX_SIZE[1] + 0.1, 0.0]));
-
-                    let collider = BoxCollider {
-                        width: ATTACK_BOX_SIZE[0],
-                        height: ATTACK_BOX_SIZE[1],
-                    };
-
-                    let parent = Parent::new(entity);
-
-                    // Create the swing at the end of the frame instead of trying
-                    // caching it and creating it after this loop
-                    lazy.create_entity(&entities)
-                        .with(parent)
-                        .with(transform)
-                        .with(collider)
-                        .with(Damage {
-                            amount: ATTACK_DAMAGE,
-                        })
-                        .with(KillAfterCollision)
-                        .with(KillAfterTime {
-                            time: time.absolute_time_seconds() + COLLIDER_TIMEOUT,
-                        })
-                        .build();
-                }
+            // Face where the player is aiming, otherwise where they are moving
+            // Keep the last facing when doing neither
+            let aim = Vector2::new(
+                input
+                    .axis_value(&AxisBinding::AimHorizontal(player.id))
+                    .unwrap_or(0.),
+                input
+                    .axis_value(&AxisBinding::AimVertical(player.id))
+                    .unwrap_or(0.),
+            );
+            if let Some(facing) = aim
+                .try_normalize(f32::EPSILON)
+                .or_else(|| Vector2::new(x_movement, y_movement).try_normalize(f32::EPSILON))
+            {
+                player.facing = facing;
             }
+
+            if actions.get(&ActionBinding::SwitchWeapon(player.id)).pressed
+                && !player.weapons.is_empty()
+            {
+                player.weapon = (player.weapon + 1) % player.weapons.len();
+                player.combo.reset();
+                player.charged_release = None;
+                debug!("Switched to {}", player.weapons[player.weapon].name);
+            }
+
+            shoot(
+                &frame,
+                entity,
+                player,
+                *transform.translation(),
+                config.radius,
+                attack_speed,
+            );
+
+            attack(&frame, entity, player, attack_speed, hitbox_size);
+        }
+    }
+}
+
+/// What every player's actions need this frame
+struct Frame<'a, 's> {
+    entities: &'a Entities<'s>,
+    lazy: &'a LazyUpdate,
+    actions: &'a ActionStates,
+    now: f64,
+}
+
+/// Starts a dash the way the player is moving or facing if they are standing still
+fn start_dash(
+    frame: &Frame,
+    player: &mut Player,
+    invulnerability: Option<&mut Invulnerability>,
+    dash: &DashConfig,
+    x_movement: f32,
+    y_movement: f32,
+) {
+    let now = frame.now;
+    if !frame.actions.get(&ActionBinding::Dash(player.id)).pressed || player.next_dash > now {
+        return;
+    }
+
+    player.dash_direction = Vector2::new(x_movement, y_movement)
+        .try_normalize(f32::EPSILON)
+        .unwrap_or(player.facing);
+    player.dash_until = now + dash.duration;
+    player.next_dash = now + dash.cooldown;
+
+    if let Some(invulnerability) = invulnerability.filter(|_| dash.invulnerable) {
+        invulnerability.until = invulnerability.until.max(player.dash_until);
+    }
+}
+
+/// Fires the player's projectile while shoot is held
+fn shoot(
+    frame: &Frame,
+    entity: Entity,
+    player: &mut Player,
+    position: Vector3<f32>,
+    radius: f32,
+    attack_speed: f32,
+) {
+    let now = frame.now;
+    let projectile = match &player.projectile {
+        Some(projectile) => projectile,
+        None => return,
+    };
+    if !frame.actions.get(&ActionBinding::Shoot(player.id)).down || player.next_shot > now {
+        return;
+    }
+
+    player.next_shot = now + projectile.cooldown / f64::from(attack_speed);
+    // Start just outside the player
+    let offset = player.facing * (radius + projectile.radius);
+    build_projectile(
+        frame.lazy.create_entity(frame.entities),
+        projectile,
+        position + Vector3::new(offset.x, offset.y, 0.0),
+        player.facing,
+        now,
+    )
+    .with(Owner { entity })
+    .build();
+}
+
+/// Swings the current weapon
+///
+/// Weapons that can be charged swing when attack is let go,
+/// others keep swinging while it is held
+fn attack(frame: &Frame, entity: Entity, player: &mut Player, attack_speed: f32, hitbox_size: f32) {
+    let now = frame.now;
+    let weapon = match player.weapons.get(player.weapon) {
+        Some(weapon) => weapon,
+        None => return,
+    };
+
+    let attack = frame.actions.get(&ActionBinding::Attack(player.id));
+    if player.next_attack > now {
+        // Letting go during the cooldown keeps the charge until it can be used
+        if attack.released && weapon.charge.is_some() {
+            player.charged_release = Some(attack.held);
         }
+        return;
+    }
+
+    let buffered = player.charged_release.take();
+    let released = if attack.released {
+        Some(attack.held)
+    } else {
+        buffered
+    };
+    let swing = if weapon.charge.is_some() {
+        released.is_some()
+    } else {
+        attack.down
+    };
+
+    // Letting go after holding long enough makes a charged attack instead
+    let charged = released.and_then(|held| weapon.charged_move(held));
+    let attack_move = if charged.is_some() {
+        player.combo.reset();
+        charged
+    } else if swing {
+        player.combo.advance(weapon, now).cloned()
+    } else {
+        None
+    };
+
+    if let Some(mut attack_move) = attack_move {
+        player.next_attack = now + attack_move.cooldown / f64::from(attack_speed);
+        if (hitbox_size - 1.0).abs() > f32::EPSILON {
+            attack_move = attack_move.scaled(1.0, hitbox_size);
+        }
+
+        // Create the swing at the end of the frame instead of trying
+        // caching it and creating it after this loop
+        build_attack(
+            frame.lazy.create_entity(frame.entities),
+            weapon.kind,
+            &attack_move,
+            player.facing,
+            now,
+        )
+        .with(Parent::new(entity))
+        .with(Owner { entity })
+        .build();
     }
 }
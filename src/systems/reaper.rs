@@ -0,0 +1,38 @@
+use amethyst::{
+    ecs::prelude::*,
+    shrev::{EventChannel, ReaderId},
+};
+use log::{trace, warn};
+
+use crate::events::DeathEvent;
+
+/// Deletes every entity that a `DeathEvent` was published for
+///
+/// This is the only system that should delete entities that die
+/// so that every other system gets a chance to react to the death
+#[derive(Default)]
+pub struct ReaperSystem {
+    reader: Option<ReaderId<DeathEvent>>,
+}
+
+impl<'s> System<'s> for ReaperSystem {
+    type SystemData = (Entities<'s>, Read<'s, EventChannel<DeathEvent>>);
+
+    fn setup(&mut self, world: &mut World) {
+        Self::SystemData::setup(world);
+        self.reader = Some(
+            world
+                .fetch_mut::<EventChannel<DeathEvent>>()
+                .register_reader(),
+        );
+    }
+
+    fn run(&mut self, (entities, deaths): Self::SystemData) {
+        for death in deaths.read(self.reader.as_mut().expect("setup wasn't called")) {
+            trace!("Reaping {:?} ({:?})", death.entity, death.cause);
+            if let Err(e) = entities.delete(death.entity) {
+                warn!("Couldn't delete {:?}: {}", death.entity, e);
+            }
+        }
+    }
+}
@@ -1,37 +1,208 @@
 // Truncation is fine for score text
 #![allow(clippy::cast_possible_truncation)]
 
-use crate::components::{Collisions, Damage, Health, Player};
+use crate::components::{Buffs, Enemy, Owner, Player};
+use crate::events::{DeathCause, DeathEvent};
+use crate::input::bindings::PlayerId;
 
 use amethyst::{
     assets::Loader,
     core::SystemDesc,
+    core::Time,
     ecs::prelude::*,
+    shrev::{EventChannel, ReaderId},
     ui::{Anchor, TtfFormat, UiText, UiTransform},
 };
+use std::fmt::Write as _;
 
+/// Seconds a combo lasts after the last kill
+const COMBO_TIMEOUT: f64 = 2.0;
+/// Multiplier added for every kill in a combo after the first
+const COMBO_STEP: f32 = 0.1;
+const MAX_COMBO_MULTIPLIER: f32 = 3.0;
+
+/// Kills made in quick succession by one player
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KillCombo {
+    pub kills: u32,
+    /// When the combo ends unless another kill is made
+    pub expires: f64,
+}
+
+impl KillCombo {
+    /// Multiplier for the score of kills made at `now`
+    #[allow(clippy::cast_precision_loss)]
+    pub fn multiplier(&self, now: f64) -> f32 {
+        if self.kills == 0 || now > self.expires {
+            1.0
+        } else {
+            (1.0 + (self.kills - 1) as f32 * COMBO_STEP).min(MAX_COMBO_MULTIPLIER)
+        }
+    }
+
+    fn add_kill(&mut self, now: f64) {
+        if now > self.expires {
+            self.kills = 0;
+        }
+        self.kills += 1;
+        self.expires = now + COMBO_TIMEOUT;
+    }
+}
+
+/// Score and kill combo of every player indexed by their `PlayerId`
 #[derive(Default)]
 pub struct Score {
-    pub player_score: u32,
+    pub player_scores: Vec<u32>,
+    pub combos: Vec<KillCombo>,
+}
+
+impl Score {
+    pub fn new(players: usize) -> Self {
+        Self {
+            player_scores: vec![0; players],
+            combos: vec![KillCombo::default(); players],
+        }
+    }
+
+    pub fn add(&mut self, player: PlayerId, amount: u32) {
+        if self.player_scores.len() <= player {
+            self.player_scores.resize(player + 1, 0);
+        }
+        self.player_scores[player] += amount;
+    }
+
+    /// Counts a kill towards `player`'s combo and adds `score` times
+    /// the combo multiplier and `multiplier`
+    #[allow(clippy::cast_sign_loss)]
+    pub fn add_kill(&mut self, player: PlayerId, score: u32, multiplier: f32, now: f64) {
+        if self.combos.len() <= player {
+            self.combos.resize(player + 1, KillCombo::default());
+        }
+        let combo = &mut self.combos[player];
+        combo.add_kill(now);
+
+        #[allow(clippy::cast_precision_loss)]
+        let amount = score as f32 * combo.multiplier(now) * multiplier;
+        self.add(player, amount.max(0.0).round() as u32);
+    }
+
+    pub fn total(&self) -> u32 {
+        self.player_scores.iter().sum()
+    }
+
+    fn multiplier(&self, player: PlayerId, now: f64) -> f32 {
+        self.combos
+            .get(player)
+            .map_or(1.0, |combo| combo.multiplier(now))
+    }
+}
+
+/// Awards the score of every enemy killed by a player
+///
+/// Kills are credited to the player that owns the attack or projectile
+/// that dealt the killing blow
+#[derive(Default)]
+pub struct ScoreSystem {
+    reader: Option<ReaderId<DeathEvent>>,
+}
+
+impl<'s> System<'s> for ScoreSystem {
+    #[allow(clippy::type_complexity)]
+    type SystemData = (
+        Read<'s, EventChannel<DeathEvent>>,
+        ReadStorage<'s, Enemy>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Owner>,
+        ReadStorage<'s, Buffs>,
+        Read<'s, Time>,
+        Write<'s, Score>,
+    );
+
+    fn setup(&mut self, world: &mut World) {
+        Self::SystemData::setup(world);
+        self.reader = Some(
+            world
+                .fetch_mut::<EventChannel<DeathEvent>>()
+                .register_reader(),
+        );
+    }
+
+    fn run(
+        &mut self,
+        (deaths, enemies, players, owners, buffs, time, mut scores): Self::SystemData,
+    ) {
+        let now = time.absolute_time_seconds();
+
+        for death in deaths.read(self.reader.as_mut().expect("setup wasn't called")) {
+            if death.cause != DeathCause::Damage {
+                continue;
+            }
+            let enemy = match enemies.get(death.entity) {
+                Some(enemy) => enemy,
+                None => continue,
+            };
+
+            // Attacks and projectiles are credited to whoever made them
+            let credited = match death.killer {
+                Some(killer) => owners.get(killer).map_or(killer, |owner| owner.entity),
+                None => continue,
+            };
+            // Enemies killed by other enemies aren't anyone's kill
+            let player = match players.get(credited) {
+                Some(player) => player,
+                None => continue,
+            };
+
+            let multiplier = buffs
+                .get(credited)
+                .map_or(1.0, |buffs| buffs.score.get(now));
+            scores.add_kill(player.id, enemy.score, multiplier, now);
+        }
+    }
 }
 
 struct ScoreText {
     pub player_score_entity: Entity,
+    // Kept around so that the text is only replaced when it changes
+    buffer: String,
 }
 
 impl ScoreText {
-    fn format_score(score: u32) -> String {
-        format!("Score: {}", score)
+    // Only splits the score up when more than one player has scored
+    fn format_score(buffer: &mut String, score: &Score, now: f64) {
+        buffer.clear();
+        // Writing to a string can't fail
+        if score.player_scores.len() <= 1 {
+            let _ = write!(
+                buffer,
+                "Score: {}  x{:.1}",
+                score.total(),
+                score.multiplier(0, now)
+            );
+        } else {
+            for (id, player_score) in score.player_scores.iter().enumerate() {
+                if id > 0 {
+                    buffer.push_str("  ");
+                }
+                let _ = write!(
+                    buffer,
+                    "P{}: {} x{:.1}",
+                    id + 1,
+                    player_score,
+                    score.multiplier(id, now)
+                );
+            }
+        }
     }
 }
 
 #[derive(Default, Debug)]
-pub struct ScoreSystemDesc;
+pub struct ScoreTextSystemDesc;
 
-// Initialize the UI entities required for the score system
-impl<'a, 'b> SystemDesc<'a, 'b, ScoreSystem> for ScoreSystemDesc {
-    fn build(self, world: &mut World) -> ScoreSystem {
-        <ScoreSystem as System<'_>>::SystemData::setup(world);
+// Initialize the UI entities required for the score text
+impl<'a, 'b> SystemDesc<'a, 'b, ScoreTextSystem> for ScoreTextSystemDesc {
+    fn build(self, world: &mut World) -> ScoreTextSystem {
+        <ScoreTextSystem as System<'_>>::SystemData::setup(world);
 
         let font = world.read_resource::<Loader>().load(
             "fonts/FiraSans-Regular.ttf",
@@ -49,58 +220,38 @@ impl<'a, 'b> SystemDesc<'a, 'b, ScoreSystem> for ScoreSystemDesc {
             100e20_f32,
             50_f32,
         );
+        let mut buffer = String::new();
+        ScoreText::format_score(&mut buffer, &Score::default(), 0.0);
         let player_score_entity = world
             .create_entity()
             .with(score_transform)
-            .with(UiText::new(
-                font,
-                ScoreText::format_score(0),
-                [1_f32; 4],
-                50_f32,
-            ))
+            .with(UiText::new(font, buffer.clone(), [1_f32; 4], 50_f32))
             .build();
 
-        ScoreSystem {
+        ScoreTextSystem {
             text: ScoreText {
                 player_score_entity,
+                buffer,
             },
         }
     }
 }
 
-pub struct ScoreSystem {
+/// Shows the score and combo multiplier of every player
+pub struct ScoreTextSystem {
     text: ScoreText,
 }
 
-impl<'s> System<'s> for ScoreSystem {
-    #[allow(clippy::type_complexity)]
-    type SystemData = (
-        ReadStorage<'s, Collisions>,
-        ReadStorage<'s, Damage>,
-        WriteStorage<'s, Health>,
-        ReadStorage<'s, Player>,
-        WriteStorage<'s, UiText>,
-        Write<'s, Score>,
-    );
+impl<'s> System<'s> for ScoreTextSystem {
+    type SystemData = (Read<'s, Score>, Read<'s, Time>, WriteStorage<'s, UiText>);
 
-    #[allow(clippy::cast_sign_loss)] // Handled by checking sign
-    fn run(
-        &mut self,
-        (collisions, damages, healths, players, mut ui_text, mut scores): Self::SystemData,
-    ) {
-        for (collision_entries, damage) in (&collisions, &damages).join() {
-            // For each collision that isn't with a player with an entity with a health
-            // Mask looks at just the bitmask without pulling up the actual component
-            // Possibly saving some execution time
-            for (_, _, _) in (collision_entries.mask(), !players.mask(), healths.mask()).join() {
-                scores.player_score += if damage.amount.is_sign_negative() {
-                    0
-                } else {
-                    damage.amount as u32
-                };
-                if let Some(text) = ui_text.get_mut(self.text.player_score_entity) {
-                    text.text = ScoreText::format_score(scores.player_score);
-                }
+    fn run(&mut self, (scores, time, mut ui_text): Self::SystemData) {
+        // Combos run out without any kills so this has to be checked every frame
+        let buffer = &mut self.text.buffer;
+        ScoreText::format_score(buffer, &scores, time.absolute_time_seconds());
+        if let Some(text) = ui_text.get_mut(self.text.player_score_entity) {
+            if text.text != *buffer {
+                text.text.clone_from(buffer);
             }
         }
     }
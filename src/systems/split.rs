@@ -0,0 +1,84 @@
+use amethyst::{
+    core::math::{Vector2, Vector3},
+    ecs::{prelude::*, LazyUpdate},
+    shrev::{EventChannel, ReaderId},
+};
+use log::debug;
+use rand::{
+    distributions::{Distribution, Uniform},
+    rngs::StdRng,
+};
+
+use crate::components::{build_enemy, SplitOnDeath};
+use crate::events::DeathEvent;
+use crate::scenes::SceneConfig;
+
+/// Splits enemies with `SplitOnDeath` into smaller enemies when they die
+///
+/// The smaller enemies are spread out evenly in a circle
+#[derive(Default)]
+pub struct SplitSystem {
+    reader: Option<ReaderId<DeathEvent>>,
+    // Seeded from the scene so that runs with the same seed split the same way
+    rng: Option<StdRng>,
+}
+
+impl<'s> System<'s> for SplitSystem {
+    type SystemData = (
+        Entities<'s>,
+        Read<'s, EventChannel<DeathEvent>>,
+        ReadStorage<'s, SplitOnDeath>,
+        Read<'s, LazyUpdate>,
+        Option<Read<'s, SceneConfig>>,
+    );
+
+    fn setup(&mut self, world: &mut World) {
+        Self::SystemData::setup(world);
+        self.reader = Some(
+            world
+                .fetch_mut::<EventChannel<DeathEvent>>()
+                .register_reader(),
+        );
+    }
+
+    fn run(&mut self, (entities, deaths, splits, lazy, config): Self::SystemData) {
+        let rng = match &config {
+            Some(config) => self
+                .rng
+                .get_or_insert_with(|| config.system_rng("split_system")),
+            // No scene has started so nothing can have died
+            None => return,
+        };
+        let angle = Uniform::new(0.0, std::f32::consts::PI * 2.0);
+
+        for death in deaths.read(self.reader.as_mut().expect("setup wasn't called")) {
+            // The reaper hasn't deleted the entity yet so its components are still around
+            let split = if let Some(split) = splits.get(death.entity) {
+                split
+            } else {
+                continue;
+            };
+
+            let kind = if let Some(kind) = split.parent.split_kind() {
+                kind
+            } else {
+                continue;
+            };
+            let count = split.parent.split.as_ref().map_or(0, |split| split.count);
+
+            debug!("{:?} split into {} pieces", death.entity, count);
+
+            let start = angle.sample(rng);
+            for i in 0..count {
+                #[allow(clippy::cast_precision_loss)]
+                let angle = start + i as f32 * std::f32::consts::PI * 2.0 / count as f32;
+                let direction = Vector2::new(angle.cos(), angle.sin());
+                // Move the pieces apart so that they don't start on top of each other
+                let position =
+                    death.position + Vector3::new(direction.x, direction.y, 0.0) * kind.radius;
+
+                build_enemy(lazy.create_entity(&entities), &kind, position, direction).build();
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+#![allow(clippy::type_repetition_in_bounds)]
+
+use amethyst::core::{math::Vector3, Time, Transform};
+use amethyst::ecs::prelude::*;
+use amethyst::shrev::EventChannel;
+use log::debug;
+
+use crate::components::{Expires, Health, Player, StatusEffects};
+use crate::events::{DeathCause, DeathEvent};
+use crate::util::transform::global_translation;
+
+/// Ticks poison and regeneration and removes effects that have run out
+///
+/// Slow, stun and shield don't do anything on their own, the systems they
+/// affect check for them instead
+#[derive(Default)]
+pub struct StatusEffectSystem {
+    // Kept around so that its memory can be reused
+    expired: Vec<Entity>,
+}
+
+#[derive(SystemData)]
+pub struct StatusEffectSystemData<'a> {
+    entities: Entities<'a>,
+    effects: StatusEffects<'a>,
+    healths: WriteStorage<'a, Health>,
+    players: ReadStorage<'a, Player>,
+    transforms: ReadStorage<'a, Transform>,
+    time: Read<'a, Time>,
+    deaths: Write<'a, EventChannel<DeathEvent>>,
+}
+
+impl<'s> System<'s> for StatusEffectSystem {
+    type SystemData = StatusEffectSystemData<'s>;
+
+    fn run(
+        &mut self,
+        StatusEffectSystemData {
+            entities,
+            mut effects,
+            mut healths,
+            players,
+            transforms,
+            time,
+            mut deaths,
+        }: Self::SystemData,
+    ) {
+        let now = time.absolute_time_seconds();
+        let delta = time.delta_seconds();
+
+        for (entity, poison, health) in (&entities, &effects.poisons, &mut healths).join() {
+            // Already died this frame
+            if health.amount <= 0.0 {
+                continue;
+            }
+
+            let mut amount = poison.damage_per_second * delta;
+            if let Some(shield) = effects.shields.get_mut(entity) {
+                amount = shield.absorb(amount);
+            }
+            health.amount -= amount;
+
+            // Same as the damage system, players running out of health ends the game instead
+            if health.amount <= 0.0 && !players.contains(entity) {
+                debug!("{:?} succumbed to poison", entity);
+                deaths.single_write(DeathEvent {
+                    entity,
+                    cause: DeathCause::Damage,
+                    position: transforms
+                        .get(entity)
+                        .map_or_else(Vector3::zeros, global_translation),
+                    killer: None,
+                });
+            }
+        }
+
+        for (regen, health) in (&effects.regens, &mut healths).join() {
+            // Dead things stay dead
+            if health.amount > 0.0 {
+                health.amount = (health.amount + regen.health_per_second * delta).min(health.max);
+            }
+        }
+
+        // Shields that are used up are gone early
+        for shield in (&mut effects.shields).join() {
+            if shield.amount <= 0.0 {
+                shield.until = now;
+            }
+        }
+
+        let expired = &mut self.expired;
+        remove_expired(&entities, &mut effects.poisons, now, expired);
+        remove_expired(&entities, &mut effects.slows, now, expired);
+        remove_expired(&entities, &mut effects.stuns, now, expired);
+        remove_expired(&entities, &mut effects.shields, now, expired);
+        remove_expired(&entities, &mut effects.regens, now, expired);
+    }
+}
+
+fn remove_expired<T: Component + Expires>(
+    entities: &Entities,
+    storage: &mut WriteStorage<T>,
+    now: f64,
+    expired: &mut Vec<Entity>,
+) {
+    expired.extend(
+        (entities, &*storage)
+            .join()
+            .filter(|(_, effect)| effect.until() <= now)
+            .map(|(entity, _)| entity),
+    );
+    for entity in expired.drain(..) {
+        storage.remove(entity);
+    }
+}
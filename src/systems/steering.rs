@@ -0,0 +1,127 @@
+use amethyst::{
+    core::{
+        math::{Vector2, Vector3},
+        Time, Transform,
+    },
+    ecs::prelude::{Join, Read, ReadStorage, System, WriteStorage},
+};
+use rand::{
+    distributions::{Distribution, Uniform},
+    rngs::StdRng,
+};
+
+use crate::{
+    components::{BehaviorMode, EnemyBehavior, Health, Player, Velocity},
+    scenes::SceneConfig,
+    util::transform::global_translation,
+};
+
+// How fast (radians/s) a wandering enemy can turn
+const WANDER_TURN_RATE: f32 = 4.0;
+
+/// Steers enemies according to their `EnemyBehavior` by changing their velocity
+#[derive(Default)]
+pub struct SteeringSystem {
+    // Player positions are cached every frame so that they aren't joined on
+    // for every enemy. Kept around so that its memory can be reused
+    players: Vec<Vector2<f32>>,
+    // Seeded from the scene so that runs with the same seed wander the same way
+    rng: Option<StdRng>,
+}
+
+impl<'s> System<'s> for SteeringSystem {
+    type SystemData = (
+        WriteStorage<'s, EnemyBehavior>,
+        WriteStorage<'s, Velocity>,
+        ReadStorage<'s, Transform>,
+        ReadStorage<'s, Player>,
+        ReadStorage<'s, Health>,
+        Read<'s, Time>,
+        Option<Read<'s, SceneConfig>>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut behaviors,
+            mut velocities,
+            transforms,
+            players,
+            healths,
+            time,
+            config,
+        ): Self::SystemData,
+    ) {
+        let rng = match &config {
+            Some(config) => self
+                .rng
+                .get_or_insert_with(|| config.system_rng("steering_system")),
+            // No scene has started so there is nothing to steer
+            None => return,
+        };
+
+        self.players.clear();
+        // Players that ran out of health can't fight back so they are left alone
+        self.players.extend(
+            (&players, healths.maybe(), &transforms)
+                .join()
+                .filter(|(_, health, _)| !matches!(health, Some(health) if health.amount <= 0.0))
+                .map(|(_, _, transform)| global_translation(transform).xy()),
+        );
+
+        let delta = time.delta_seconds();
+        let turn = Uniform::new_inclusive(-WANDER_TURN_RATE, WANDER_TURN_RATE);
+
+        for (behavior, velocity, transform) in (&mut behaviors, &mut velocities, &transforms).join()
+        {
+            let position = global_translation(transform).xy();
+
+            let closest = self.players.iter().min_by(|a, b| {
+                (*a - position)
+                    .norm_squared()
+                    .partial_cmp(&(*b - position).norm_squared())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+
+            let direction = match (behavior.mode, closest) {
+                (BehaviorMode::Chase, Some(player)) => player - position,
+                (BehaviorMode::Flee, Some(player)) => position - player,
+                (BehaviorMode::Orbit { radius }, Some(player)) => {
+                    let offset = position - player;
+                    let outwards = offset
+                        .try_normalize(f32::EPSILON)
+                        .unwrap_or_else(Vector2::x);
+                    // Go around the player while correcting the distance to it.
+                    // A radius of 0 would divide by 0 so that just heads straight in
+                    let tangent = Vector2::new(-outwards.y, outwards.x);
+                    tangent + outwards * (radius - offset.norm()) / radius.max(f32::EPSILON)
+                }
+                // Wander when there is no one to chase
+                _ => {
+                    behavior.wander_angle += turn.sample(rng) * delta;
+                    Vector2::new(behavior.wander_angle.cos(), behavior.wander_angle.sin())
+                }
+            };
+
+            let desired = direction
+                .try_normalize(f32::EPSILON)
+                .map_or_else(Vector2::zeros, |direction| direction * behavior.max_speed);
+
+            // Limit how quickly the velocity can change so that movement is smooth
+            let current = velocity.value.xy();
+            let steering = cap_magnitude(desired - current, behavior.max_acceleration * delta);
+            let new_velocity = cap_magnitude(current + steering, behavior.max_speed);
+
+            velocity.value = Vector3::new(new_velocity.x, new_velocity.y, velocity.value.z);
+        }
+    }
+}
+
+fn cap_magnitude(vector: Vector2<f32>, max: f32) -> Vector2<f32> {
+    let magnitude = vector.norm();
+    if magnitude > max {
+        vector * (max / magnitude)
+    } else {
+        vector
+    }
+}
@@ -4,20 +4,37 @@ use amethyst::{
     ecs::prelude::{Join, Read, ReadStorage, System, WriteStorage},
 };
 
-use crate::components::Velocity;
+use crate::components::{Slow, Stun, Velocity};
+
+// Fraction of knockback that is left after a second
+const KNOCKBACK_REMAINING: f32 = 0.001;
 
 pub struct VelocitySystem;
 
 impl<'s> System<'s> for VelocitySystem {
     type SystemData = (
-        ReadStorage<'s, Velocity>,
+        WriteStorage<'s, Velocity>,
+        ReadStorage<'s, Slow>,
+        ReadStorage<'s, Stun>,
         WriteStorage<'s, Transform>,
         Read<'s, Time>,
     );
 
-    fn run(&mut self, (velocities, mut locals, time): Self::SystemData) {
-        for (velocity, local) in (&velocities, &mut locals).join() {
-            local.prepend_translation(velocity.value * time.delta_seconds());
+    fn run(&mut self, (mut velocities, slows, stuns, mut locals, time): Self::SystemData) {
+        let delta = time.delta_seconds();
+        let decay = KNOCKBACK_REMAINING.powf(delta);
+
+        for (velocity, slow, stun, local) in
+            (&mut velocities, slows.maybe(), stuns.maybe(), &mut locals).join()
+        {
+            // Stunned entities can't move on their own but can still be knocked around
+            let multiplier = if stun.is_some() {
+                0.0
+            } else {
+                slow.map_or(1.0, |slow| slow.multiplier)
+            };
+            local.prepend_translation((velocity.value * multiplier + velocity.knockback) * delta);
+            velocity.knockback *= decay;
         }
     }
 }
@@ -0,0 +1,94 @@
+//! #Allocation counting
+//! A global allocator that counts every allocation so that benchmarks
+//! (and tests) can check that hot paths don't allocate
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::ops::Sub;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Wraps the system allocator and counts allocations
+pub struct CountingAllocator;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    // Systems run in parallel so scopes have to use the count for
+    // their own thread. Const so that reading it never allocates
+    static THREAD_STATS: Cell<AllocStats> = const {
+        Cell::new(AllocStats {
+            allocations: 0,
+            bytes: 0,
+        })
+    };
+}
+
+/// Number of allocations and bytes allocated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub allocations: u64,
+    pub bytes: u64,
+}
+
+impl Sub for AllocStats {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            allocations: self.allocations - other.allocations,
+            bytes: self.bytes - other.bytes,
+        }
+    }
+}
+
+/// Allocations made by every thread since the start of the program
+#[cfg(feature = "benchmark")]
+pub fn global_stats() -> AllocStats {
+    AllocStats {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed),
+        bytes: BYTES.load(Ordering::Relaxed),
+    }
+}
+
+/// Allocations made by the current thread since it started
+pub fn thread_stats() -> AllocStats {
+    THREAD_STATS.with(Cell::get)
+}
+
+fn record(size: usize) {
+    let size = size as u64;
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    BYTES.fetch_add(size, Ordering::Relaxed);
+
+    // Fails if the thread is being torn down which is fine to ignore
+    let _ = THREAD_STATS.try_with(|stats| {
+        let mut current = stats.get();
+        current.allocations += 1;
+        current.bytes += size;
+        stats.set(current);
+    });
+}
+
+// SAFETY: Every call is forwarded to the system allocator unchanged
+#[allow(unsafe_code)]
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        record(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    // Growing a Vec is an allocation as far as we are concerned
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
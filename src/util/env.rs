@@ -0,0 +1,15 @@
+/// Reads and parses an environment variable
+///
+/// Falls back to `default` when the variable isn't set or can't be parsed
+pub fn get_variable<F: std::str::FromStr>(variable: &str, default: F) -> F {
+    if let Ok(value) = std::env::var(variable) {
+        if let Ok(value) = value.parse() {
+            value
+        } else {
+            log::warn!("Invalid {}: '{}'", variable, value);
+            default
+        }
+    } else {
+        default
+    }
+}
@@ -2,28 +2,109 @@
 //! This module creates a struct that is used to create a summary
 //! of frame times when running a benchmark
 use amethyst::core::timing::Stopwatch;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
 
-/// Writes an advance frame to the bench res that it is attached to
-/// when it goes out of scope
+use crate::scenes::SceneConfig;
+use crate::util::alloc_counter::{self, AllocStats};
+use crate::util::env::get_variable;
+
+/// Writes an advance frame to the named scope of the bench
+/// that it is attached to when it goes out of scope
+///
+/// Allocations are counted for the current thread only since
+/// other systems may be running at the same time
 pub struct ScopeTimer<'s> {
     watch: Stopwatch,
-    bench: &'s mut BenchRes,
+    allocs: AllocStats,
+    name: &'static str,
+    bench: &'s FrameBench,
 }
 
 impl<'s> ScopeTimer<'s> {
-    pub fn new(bench: &'s mut BenchRes) -> Self {
+    pub fn new(bench: &'s FrameBench, name: &'static str) -> Self {
         let mut watch = Stopwatch::new();
         watch.start();
-        ScopeTimer { watch, bench }
+        ScopeTimer {
+            watch,
+            allocs: alloc_counter::thread_stats(),
+            name,
+            bench,
+        }
     }
 }
 
 impl<'s> Drop for ScopeTimer<'s> {
     fn drop(&mut self) {
         let elapsed = self.watch.elapsed();
-        self.bench.advance_frame(elapsed.as_secs_f64());
+        let allocs = alloc_counter::thread_stats() - self.allocs;
+        self.bench
+            .record_scope(self.name, elapsed.as_secs_f64(), allocs);
+    }
+}
+
+// Smallest frame time the histogram can tell apart (100ns)
+const HISTOGRAM_MIN: f64 = 1e-7;
+// Each bucket is 2% wider than the last so quantiles are accurate to ~1%
+const HISTOGRAM_GROWTH: f64 = 1.02;
+// 1e-7 * 1.02^1000 is roughly 40 seconds which is more than enough
+const HISTOGRAM_BUCKETS: usize = 1000;
+
+/// Default frame budget in seconds (60 FPS)
+const DEFAULT_BUDGET: f64 = 1.0 / 60.0;
+
+/// Log scale histogram of frame times used to find percentiles
+/// without having to store every frame
+struct Histogram {
+    buckets: Vec<u32>,
+}
+
+impl Histogram {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Checked by the if
+    fn bucket(delta_time: f64) -> usize {
+        if delta_time <= HISTOGRAM_MIN {
+            0
+        } else {
+            let index = (delta_time / HISTOGRAM_MIN).ln() / HISTOGRAM_GROWTH.ln();
+            (index as usize).min(HISTOGRAM_BUCKETS - 1)
+        }
+    }
+
+    fn record(&mut self, delta_time: f64) {
+        self.buckets[Self::bucket(delta_time)] += 1;
+    }
+
+    /// Upper bound of the bucket that contains the `q`th quantile
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        clippy::cast_sign_loss
+    )]
+    fn quantile(&self, q: f64, count: u32) -> f64 {
+        let target = (q * f64::from(count)).ceil().max(1.0) as u32;
+
+        let mut seen = 0;
+        for (index, amount) in self.buckets.iter().enumerate() {
+            seen += amount;
+            if seen >= target {
+                return HISTOGRAM_MIN * HISTOGRAM_GROWTH.powi(index as i32 + 1);
+            }
+        }
+
+        f64::NAN
+    }
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: vec![0; HISTOGRAM_BUCKETS],
+        }
     }
 }
 
@@ -33,28 +114,129 @@ pub struct BenchRes {
     min: f64,
     total: f64,
     frame_count: u32,
+    // Running variance using Welford's algorithm
+    mean: f64,
+    m2: f64,
+    budget: f64,
+    over_budget: u32,
+    histogram: Histogram,
+    allocs: AllocStats,
+    max_allocations: u64,
 }
 
 impl BenchRes {
-    pub fn advance_frame(&mut self, delta_time: f64) {
+    pub fn new(budget: f64) -> Self {
+        Self {
+            max: 0_f64,
+            min: std::f64::MAX,
+            total: 0_f64,
+            frame_count: 0,
+            mean: 0_f64,
+            m2: 0_f64,
+            budget,
+            over_budget: 0,
+            histogram: Histogram::default(),
+            allocs: AllocStats::default(),
+            max_allocations: 0,
+        }
+    }
+
+    pub fn advance_frame(&mut self, delta_time: f64, allocs: AllocStats) {
+        self.allocs.allocations += allocs.allocations;
+        self.allocs.bytes += allocs.bytes;
+        self.max_allocations = self.max_allocations.max(allocs.allocations);
+
         self.frame_count += 1;
         self.total += delta_time;
         self.min = self.min.min(delta_time);
         self.max = self.max.max(delta_time);
+
+        let delta = delta_time - self.mean;
+        self.mean += delta / f64::from(self.frame_count);
+        self.m2 += delta * (delta_time - self.mean);
+
+        if delta_time > self.budget {
+            self.over_budget += 1;
+        }
+
+        self.histogram.record(delta_time);
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    pub fn allocations_per_frame(&self) -> f64 {
+        self.allocs.allocations as f64 / f64::from(self.frame_count)
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    pub fn bytes_per_frame(&self) -> f64 {
+        self.allocs.bytes as f64 / f64::from(self.frame_count)
+    }
+
+    pub fn std_dev(&self) -> f64 {
+        (self.m2 / f64::from(self.frame_count)).sqrt()
     }
 
-    pub fn time_scope(&mut self) -> ScopeTimer {
-        ScopeTimer::new(self)
+    /// Frame time at the given quantile (0.0 - 1.0)
+    ///
+    /// Clamped to the shortest and longest frame since the histogram
+    /// only knows which bucket a frame landed in
+    pub fn percentile(&self, q: f64) -> f64 {
+        // Like the average there is no answer without any frames
+        if self.frame_count == 0 {
+            return f64::NAN;
+        }
+
+        self.histogram
+            .quantile(q, self.frame_count)
+            .max(self.min)
+            .min(self.max)
     }
 }
 
 impl Default for BenchRes {
     fn default() -> Self {
+        Self::new(DEFAULT_BUDGET)
+    }
+}
+
+/// Serializable summary of a `BenchRes`
+#[derive(Serialize)]
+pub struct BenchSummary {
+    avg: f64,
+    min: f64,
+    max: f64,
+    total: f64,
+    frame_count: u32,
+    std_dev: f64,
+    p50: f64,
+    p90: f64,
+    p99: f64,
+    p999: f64,
+    budget: f64,
+    over_budget: u32,
+    allocations_per_frame: f64,
+    bytes_per_frame: f64,
+    max_allocations: u64,
+}
+
+impl From<&BenchRes> for BenchSummary {
+    fn from(res: &BenchRes) -> Self {
         Self {
-            max: 0_f64,
-            min: std::f64::MAX,
-            total: 0_f64,
-            frame_count: 0,
+            avg: res.total / f64::from(res.frame_count),
+            min: res.min,
+            max: res.max,
+            total: res.total,
+            frame_count: res.frame_count,
+            std_dev: res.std_dev(),
+            p50: res.percentile(0.5),
+            p90: res.percentile(0.9),
+            p99: res.percentile(0.99),
+            p999: res.percentile(0.999),
+            budget: res.budget,
+            over_budget: res.over_budget,
+            allocations_per_frame: res.allocations_per_frame(),
+            bytes_per_frame: res.bytes_per_frame(),
+            max_allocations: res.max_allocations,
         }
     }
 }
@@ -66,49 +248,232 @@ impl fmt::Display for BenchRes {
             f,
             "Avg frame time: {}\nShortest frame time: {}\nLongest frame time: {}\nTotal Time: {}\nFrame Count: {}",
             avg_frame, self.min, self.max, self.total, self.frame_count
+        )?;
+        write!(
+            f,
+            "\nStandard deviation: {}\np50: {}\np90: {}\np99: {}\np99.9: {}\nFrames over {}s budget: {}",
+            self.std_dev(),
+            self.percentile(0.5),
+            self.percentile(0.9),
+            self.percentile(0.99),
+            self.percentile(0.999),
+            self.budget,
+            self.over_budget
+        )?;
+        write!(
+            f,
+            "\nAllocations per frame: {}\nBytes allocated per frame: {}\nMost allocations in a frame: {}",
+            self.allocations_per_frame(),
+            self.bytes_per_frame(),
+            self.max_allocations
         )
     }
 }
 
+/// Describes what a benchmark was run against
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct BenchMeta {
+    scene: String,
+    /// Either `rendered` or `headless`
+    mode: &'static str,
+    enemy_count: usize,
+    area_scale: f32,
+    seed: u64,
+    git_revision: String,
+}
+
+impl BenchMeta {
+    /// The git revision is read from `GIT_REVISION` since the executable
+    /// can't know which commit it was built from on its own
+    pub fn new(scene: &str, mode: &'static str, config: &SceneConfig) -> Self {
+        Self {
+            scene: scene.to_string(),
+            mode,
+            enemy_count: config.enemy_count,
+            area_scale: config.area_scale,
+            seed: config.seed,
+            git_revision: std::env::var("GIT_REVISION").unwrap_or_else(|_| "unknown".to_string()),
+        }
+    }
+}
+
+/// How long a benchmark runs for
+#[derive(Clone, Debug, Serialize)]
+pub struct BenchSettings {
+    /// Seconds of recorded frames before the benchmark ends
+    duration: f64,
+    /// Seconds at the start that are excluded from the results
+    warmup: f64,
+    /// Ends the benchmark early after this many recorded frames
+    max_frames: Option<u32>,
+    /// Frames longer than this (in seconds) count as over budget
+    budget: f64,
+}
+
+impl BenchSettings {
+    /// | Variable          | Default   |
+    /// |-------------------|-----------|
+    /// | `BENCH_DURATION`  | 30        |
+    /// | `BENCH_WARMUP`    | 0         |
+    /// | `BENCH_FRAMES`    | unlimited |
+    /// | `FRAME_BUDGET_MS` | 16.6      |
+    pub fn from_env() -> Self {
+        let max_frames = get_variable("BENCH_FRAMES", 0);
+
+        Self {
+            duration: get_variable("BENCH_DURATION", 30.0),
+            warmup: get_variable("BENCH_WARMUP", 0.0),
+            max_frames: if max_frames == 0 {
+                None
+            } else {
+                Some(max_frames)
+            },
+            budget: get_variable("FRAME_BUDGET_MS", DEFAULT_BUDGET * 1000.0) / 1000.0,
+        }
+    }
+
+    /// Uses `frames` as the frame limit unless one was already set
+    pub fn with_default_frames(mut self, frames: u32) -> Self {
+        self.max_frames.get_or_insert(frames);
+        self
+    }
+}
+
+impl Default for BenchSettings {
+    fn default() -> Self {
+        Self {
+            duration: 30.0,
+            warmup: 0.0,
+            max_frames: None,
+            budget: DEFAULT_BUDGET,
+        }
+    }
+}
+
+/// One line of the benchmark output file
+#[derive(Serialize)]
+struct BenchReport<'a> {
+    #[serde(flatten)]
+    meta: &'a BenchMeta,
+    settings: &'a BenchSettings,
+    fps: f64,
+    main: BenchSummary,
+    systems: BTreeMap<&'a str, BenchSummary>,
+}
+
 /// All benching data
+///
+/// The per system results are behind a mutex so that systems only need
+/// to `Read` the bench. Otherwise every timed system would be forced to
+/// run one after another
 #[derive(Default)]
 pub struct FrameBench {
+    meta: BenchMeta,
+    settings: BenchSettings,
     main: BenchRes,
-    systems: HashMap<String, BenchRes>,
+    systems: Mutex<HashMap<&'static str, BenchRes>>,
+    // Time since the first frame, including the warm up
+    elapsed: f64,
+    // Set once the warm up is over
+    recording: AtomicBool,
+    // Global allocation count at the end of the last frame
+    last_allocs: AllocStats,
 }
 
 impl FrameBench {
+    pub fn new(meta: BenchMeta, settings: BenchSettings) -> Self {
+        log::info!("Benchmarking with {:?}", settings);
+
+        Self {
+            meta,
+            main: BenchRes::new(settings.budget),
+            settings,
+            last_allocs: alloc_counter::global_stats(),
+            ..Self::default()
+        }
+    }
+
+    pub fn fps(&self) -> f64 {
+        f64::from(self.main.frame_count) / self.main.total
+    }
+
     pub fn should_end(&self) -> bool {
-        self.main.total >= 30.0
+        self.main.total >= self.settings.duration
+            || matches!(
+                self.settings.max_frames,
+                Some(max_frames) if self.main.frame_count >= max_frames
+            )
     }
 
     pub fn advance_frame(&mut self, delta_time: f64) {
-        self.main.advance_frame(delta_time);
+        self.elapsed += delta_time;
+
+        let allocs = alloc_counter::global_stats();
+        let frame_allocs = allocs - self.last_allocs;
+        self.last_allocs = allocs;
+
+        if self.recording.load(Ordering::Relaxed) {
+            self.main.advance_frame(delta_time, frame_allocs);
+        } else if self.elapsed >= self.settings.warmup {
+            // Start recording from the next frame so that no system
+            // results are recorded from halfway through a frame
+            self.recording.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn time_scope(&self, name: &'static str) -> ScopeTimer {
+        ScopeTimer::new(self, name)
+    }
+
+    fn record_scope(&self, name: &'static str, delta_time: f64, allocs: AllocStats) {
+        if !self.recording.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let budget = self.main.budget;
+        self.systems
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_insert_with(|| BenchRes::new(budget))
+            .advance_frame(delta_time, allocs);
     }
 
-    pub fn time_scope(&mut self, name: String) -> ScopeTimer {
-        let bench = self.systems.entry(name).or_insert_with(BenchRes::default);
-        bench.time_scope()
+    fn report(&self) -> BenchReport<'_> {
+        BenchReport {
+            meta: &self.meta,
+            settings: &self.settings,
+            fps: self.fps(),
+            main: BenchSummary::from(&self.main),
+            systems: self
+                .systems
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(name, res)| (*name, BenchSummary::from(res)))
+                .collect(),
+        }
     }
 
     // Saves to file. Uses an append so that we can have multiple benchmarks
     // in the same file
     //
-    // Uses an environment variable for benchmark name OR uses bench.out
+    // Each benchmark is written as a single line of JSON
+    // Uses an environment variable for benchmark name OR uses bench.json
     pub fn save_to_file(&self) -> std::io::Result<()> {
         use std::fs::OpenOptions;
         use std::io::prelude::*;
-        let file_name: String = std::env::var("BENCHMARK_OUT").unwrap_or("bench.out".to_string());
+        let file_name: String =
+            std::env::var("BENCHMARK_OUT").unwrap_or_else(|_| "bench.json".to_string());
 
         let mut file = OpenOptions::new()
             .create(true)
             .write(true)
             .append(true)
-            .open(file_name)
-            .unwrap();
+            .open(file_name)?;
 
-        writeln!(file, "{}", self)?;
-        writeln!(file, "-------------------")?;
+        serde_json::to_writer(&mut file, &self.report())?;
+        writeln!(file)?;
 
         Ok(())
     }
@@ -116,13 +481,93 @@ impl FrameBench {
 
 impl fmt::Display for FrameBench {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let fps = f64::from(self.main.frame_count) / self.main.total;
-        write!(f, "Performance summary: \nAvg FPS: {}\n{}", fps, self.main)?;
+        write!(
+            f,
+            "Performance summary for <{}>: \nAvg FPS: {}\n{}",
+            self.meta.scene,
+            self.fps(),
+            self.main
+        )?;
 
-        for (key, value) in &self.systems {
+        for (key, value) in self.systems.lock().unwrap().iter() {
             write!(f, "\nPerformance for <{}>\n{}", key, value)?;
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Percentiles are the upper bound of a bucket so they can be up to
+    /// one bucket above the real value
+    fn assert_within_bucket(actual: f64, expected: f64) {
+        assert!(
+            actual >= expected * (1.0 - 1e-9) && actual <= expected * HISTOGRAM_GROWTH,
+            "{} isn't within a bucket of {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn percentiles_of_uniform_frames() {
+        let mut res = BenchRes::default();
+        // 1ms to 100ms in 0.1ms steps
+        for i in 1..=1000 {
+            res.advance_frame(f64::from(i) * 1e-4, AllocStats::default());
+        }
+
+        assert_within_bucket(res.percentile(0.5), 0.05);
+        assert_within_bucket(res.percentile(0.95), 0.095);
+        assert_within_bucket(res.percentile(0.99), 0.099);
+        assert_within_bucket(res.percentile(1.0), 0.1);
+    }
+
+    #[test]
+    fn std_dev_matches_direct_computation() {
+        let frames: Vec<f64> = (0..500)
+            .map(|i| 0.016 + f64::from(i % 7) * 0.003 - f64::from(i % 3) * 0.001)
+            .collect();
+
+        let mut res = BenchRes::default();
+        for &frame in &frames {
+            res.advance_frame(frame, AllocStats::default());
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let count = frames.len() as f64;
+        let mean = frames.iter().sum::<f64>() / count;
+        let variance = frames
+            .iter()
+            .map(|frame| (frame - mean).powi(2))
+            .sum::<f64>()
+            / count;
+
+        assert!((res.std_dev() - variance.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn single_frame() {
+        let mut res = BenchRes::default();
+        res.advance_frame(0.02, AllocStats::default());
+
+        assert!(res.std_dev().abs() < f64::EPSILON);
+        // Clamped to the only frame instead of the top of its bucket
+        for &q in &[0.0, 0.5, 0.99, 1.0] {
+            assert!((res.percentile(q) - 0.02).abs() < f64::EPSILON);
+        }
+        assert_eq!(res.over_budget, 1);
+    }
+
+    #[test]
+    fn no_frames() {
+        let res = BenchRes::default();
+
+        assert!(res.std_dev().is_nan());
+        assert!(res.percentile(0.5).is_nan());
+        assert_eq!(res.over_budget, 0);
+    }
+}
@@ -1,3 +1,10 @@
+#[cfg(any(test, feature = "benchmark"))]
+pub mod alloc_counter;
+pub mod env;
 #[cfg(feature = "benchmark")]
 pub mod frame_bench;
+pub mod pause;
+#[cfg(feature = "benchmark")]
+pub mod sim_bench;
+pub mod timed;
 pub mod transform;
@@ -0,0 +1,39 @@
+//! Pausing gameplay without stopping the whole dispatcher
+//!
+//! Systems wrapped with `pausable` skip running while the `Paused` resource
+//! is set, so that states like the upgrade choice can freeze the game
+//! while the UI keeps working
+use amethyst::ecs::prelude::{Read, System, SystemData, World};
+
+/// Set to stop every pausable system from running
+#[derive(Default)]
+pub struct Paused(pub bool);
+
+/// A system that does nothing while the game is paused
+pub struct Pausable<S> {
+    system: S,
+}
+
+impl<'s, S> System<'s> for Pausable<S>
+where
+    S: System<'s>,
+    S::SystemData: SystemData<'s>,
+{
+    type SystemData = (S::SystemData, Read<'s, Paused>);
+
+    fn run(&mut self, (data, paused): Self::SystemData) {
+        if !paused.0 {
+            self.system.run(data);
+        }
+    }
+
+    // Forward setup so that systems with a custom setup still get it
+    fn setup(&mut self, world: &mut World) {
+        <Read<'_, Paused> as SystemData>::setup(world);
+        self.system.setup(world);
+    }
+}
+
+pub const fn pausable<S>(system: S) -> Pausable<S> {
+    Pausable { system }
+}
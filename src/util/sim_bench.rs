@@ -0,0 +1,90 @@
+//! #Headless benchmarks
+//! Steps the gameplay systems for a fixed number of ticks without a window
+//! so that the results aren't drowned out by rendering
+use amethyst::{
+    core::{bundle::SystemBundle, timing::Stopwatch, Time},
+    ecs::prelude::{DispatcherBuilder, World, WorldExt},
+};
+
+use crate::{
+    components::{initialize_players, EnemyCatalogue, PickupCatalogue, PlayerConfig, UpgradeTable},
+    scenes::{self, Scene, SceneConfig},
+    systems::GameplayBundle,
+    util::frame_bench::{BenchMeta, BenchSettings, FrameBench},
+};
+
+// Number of ticks when `BENCH_FRAMES` isn't set
+const DEFAULT_TICKS: u32 = 1000;
+// Every tick simulates the same amount of time so the results are repeatable
+const TICK_SECONDS: f32 = 1.0 / 60.0;
+
+/// Returns the scene when the game is started with `--headless <scene>`
+pub fn get_headless_scene_cli() -> Option<&'static Scene> {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 3 || args[1] != "--headless" {
+        None
+    } else {
+        let scene_name = &args[2];
+
+        if let Some(scene) = scenes::get_scene(scene_name) {
+            Some(scene)
+        } else {
+            panic!("`{}` is an invalid scene name!", scene_name);
+        }
+    }
+}
+
+pub fn run_headless(
+    scene: &'static Scene,
+    enemies: EnemyCatalogue,
+    player: PlayerConfig,
+    pickups: PickupCatalogue,
+    upgrades: UpgradeTable,
+) -> amethyst::Result<()> {
+    let mut world = World::new();
+    let mut builder = DispatcherBuilder::new();
+    GameplayBundle::default().build(&mut world, &mut builder)?;
+
+    let mut dispatcher = builder.build();
+    dispatcher.setup(&mut world);
+
+    let config = SceneConfig::from_env();
+    world.insert(FrameBench::new(
+        BenchMeta::new(scene.name, "headless", &config),
+        BenchSettings::from_env().with_default_frames(DEFAULT_TICKS),
+    ));
+    world.insert(config);
+    world.insert(enemies);
+    world.insert(player);
+    world.insert(pickups);
+    world.insert(upgrades);
+
+    (scene.initializer)(&mut world);
+    initialize_players(&mut world, scene.players);
+    world.maintain();
+
+    loop {
+        world
+            .write_resource::<Time>()
+            .set_delta_seconds(TICK_SECONDS);
+
+        let mut watch = Stopwatch::new();
+        watch.start();
+        dispatcher.dispatch(&world);
+        world.maintain();
+        let elapsed = watch.elapsed();
+
+        let mut bench = world.write_resource::<FrameBench>();
+        bench.advance_frame(elapsed.as_secs_f64());
+        if bench.should_end() {
+            break;
+        }
+    }
+
+    let bench = world.read_resource::<FrameBench>();
+    log::info!("{}", *bench);
+    bench.save_to_file()?;
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+//! Opt-in timing of systems for benchmarks
+//!
+//! Wrapping a system with `timed` records how long it runs every frame
+//! in the `FrameBench` without the system needing to know about it.
+//! Without the benchmark feature the system is passed through untouched
+#[cfg(feature = "benchmark")]
+use amethyst::{
+    core::SystemDesc,
+    ecs::prelude::{Read, System, SystemData, World},
+};
+
+#[cfg(feature = "benchmark")]
+use crate::util::frame_bench::FrameBench;
+
+/// A system that records its run time under `name`
+#[cfg(feature = "benchmark")]
+pub struct Timed<S> {
+    name: &'static str,
+    system: S,
+}
+
+#[cfg(feature = "benchmark")]
+impl<'s, S> System<'s> for Timed<S>
+where
+    S: System<'s>,
+    S::SystemData: SystemData<'s>,
+{
+    type SystemData = (S::SystemData, Read<'s, FrameBench>);
+
+    fn run(&mut self, (data, bench): Self::SystemData) {
+        // We want this to last the whole run so we must store it as a variable
+        let _scope = bench.time_scope(self.name);
+        self.system.run(data);
+    }
+
+    // Forward setup so that systems with a custom setup still get it
+    fn setup(&mut self, world: &mut World) {
+        <Read<'_, FrameBench> as SystemData>::setup(world);
+        self.system.setup(world);
+    }
+}
+
+/// Builds a `Timed` system out of a system desc
+#[cfg(feature = "benchmark")]
+pub struct TimedDesc<D> {
+    name: &'static str,
+    desc: D,
+}
+
+#[cfg(feature = "benchmark")]
+impl<'a, 'b, D, S> SystemDesc<'a, 'b, Timed<S>> for TimedDesc<D>
+where
+    D: SystemDesc<'a, 'b, S>,
+    S: for<'c> System<'c> + 'a,
+    Timed<S>: for<'c> System<'c>,
+{
+    fn build(self, world: &mut World) -> Timed<S> {
+        <Read<'_, FrameBench> as SystemData>::setup(world);
+        Timed {
+            name: self.name,
+            system: self.desc.build(world),
+        }
+    }
+}
+
+#[cfg(feature = "benchmark")]
+pub const fn timed<S>(name: &'static str, system: S) -> Timed<S> {
+    Timed { name, system }
+}
+
+#[cfg(not(feature = "benchmark"))]
+pub const fn timed<S>(_name: &'static str, system: S) -> S {
+    system
+}
+
+#[cfg(feature = "benchmark")]
+pub const fn timed_desc<D>(name: &'static str, desc: D) -> TimedDesc<D> {
+    TimedDesc { name, desc }
+}
+
+#[cfg(not(feature = "benchmark"))]
+pub const fn timed_desc<D>(_name: &'static str, desc: D) -> D {
+    desc
+}